@@ -32,37 +32,67 @@ Register-ArgumentCompleter -Native -CommandName 'dust' -ScriptBlock {
             [CompletionResult]::new('--ignore-directory', '--ignore-directory', [CompletionResultType]::ParameterName, 'Exclude any file or directory with this path')
             [CompletionResult]::new('-I', '-I ', [CompletionResultType]::ParameterName, 'Exclude any file or directory with a regex matching that listed in this file, the file entries will be added to the ignore regexs provided by --invert_filter')
             [CompletionResult]::new('--ignore-all-in-file', '--ignore-all-in-file', [CompletionResultType]::ParameterName, 'Exclude any file or directory with a regex matching that listed in this file, the file entries will be added to the ignore regexs provided by --invert_filter')
-            [CompletionResult]::new('-z', '-z', [CompletionResultType]::ParameterName, 'Minimum size file to include in output')
-            [CompletionResult]::new('--min-size', '--min-size', [CompletionResultType]::ParameterName, 'Minimum size file to include in output')
+            [CompletionResult]::new('--exclude', '--exclude', [CompletionResultType]::ParameterName, 'Exclude any file or directory matching this glob, eg: "**/node_modules", "*.o" or "build/*/cache". Globs match the last components of a path below the scanned directory, a leading / anchors them to it and a trailing / only matches directories')
+            [CompletionResult]::new('--exclude-from', '--exclude-from', [CompletionResultType]::ParameterName, 'Exclude any file or directory matching a glob listed in this file, one per line. Lines starting with # are comments')
+            [CompletionResult]::new('--exclude-fstype', '--exclude-fstype', [CompletionResultType]::ParameterName, 'Exclude filesystems of this type, eg: nfs,fuse.sshfs. Virtual filesystems like proc and sysfs are then excluded too unless included (Linux only)')
+            [CompletionResult]::new('--include-fstype', '--include-fstype', [CompletionResultType]::ParameterName, 'Only include filesystems of this type, eg: ext4,btrfs (Linux only)')
+            [CompletionResult]::new('--columns', '--columns', [CompletionResultType]::ParameterName, 'Columns to show for each entry in place of the size, eg: disk,files,newest. The entries shown, their order and the bars still go by the size')
+            [CompletionResult]::new('--heatmap', '--heatmap', [CompletionResultType]::ParameterName, 'Color the bars, or the names in place of LS_COLORS, by the age of the newest file below: from green (this week) to red (over 2 years)')
+            [CompletionResult]::new('--per-dir', '--per-dir', [CompletionResultType]::ParameterName, 'Show at most this many entries in each directory, down to --depth, instead of the biggest entries of the whole tree. The number of lines (-n) does not apply')
+            [CompletionResult]::new('--min-parent-percent', '--min-parent-percent', [CompletionResultType]::ParameterName, 'Only show entries taking at least this percent of their directory, the same as -z PERCENT%p')
+            [CompletionResult]::new('-z', '-z', [CompletionResultType]::ParameterName, 'Minimum size file to include in output, eg: 10M, 1% of the total or 5%p of the parent directory. Percents can not be used with filetime')
+            [CompletionResult]::new('--min-size', '--min-size', [CompletionResultType]::ParameterName, 'Minimum size file to include in output, eg: 10M, 1% of the total or 5%p of the parent directory. Percents can not be used with filetime')
+            [CompletionResult]::new('--max-size', '--max-size', [CompletionResultType]::ParameterName, 'Only count files up to this size, eg: 4K. Unlike min-size this applies to each file so totals only include the smaller files')
+            [CompletionResult]::new('--size-range', '--size-range', [CompletionResultType]::ParameterName, 'Only count files with a size in this range, eg: 4K..1M, ..4K or 1G..')
+            [CompletionResult]::new('--user', '--user', [CompletionResultType]::ParameterName, 'Only count files owned by this user, a name or a uid. Can be repeated')
+            [CompletionResult]::new('--group', '--group', [CompletionResultType]::ParameterName, 'Only count files owned by this group, a name or a gid. Can be repeated')
+            [CompletionResult]::new('--perm', '--perm', [CompletionResultType]::ParameterName, 'Only count files with these permission bits, as with find: 644 exactly, -4000 or -u+s for all of the bits (setuid), /o+w for any of them (world-writable)')
             [CompletionResult]::new('-v', '-v', [CompletionResultType]::ParameterName, 'Exclude filepaths matching this regex. To ignore png files type: -v "\.png$"')
             [CompletionResult]::new('--invert-filter', '--invert-filter', [CompletionResultType]::ParameterName, 'Exclude filepaths matching this regex. To ignore png files type: -v "\.png$"')
             [CompletionResult]::new('-e', '-e', [CompletionResultType]::ParameterName, 'Only include filepaths matching this regex. For png files type: -e "\.png$"')
             [CompletionResult]::new('--filter', '--filter', [CompletionResultType]::ParameterName, 'Only include filepaths matching this regex. For png files type: -e "\.png$"')
+            [CompletionResult]::new('--match-on', '--match-on', [CompletionResultType]::ParameterName, 'What the -e, -v and -I regexes are matched against: the whole path, the file name alone or the path relative to the directory being scanned')
+            [CompletionResult]::new('--where', '--where', [CompletionResultType]::ParameterName, 'Only include files matching this expression, eg: ''size > 100M and (ext in [log, gz] or mtime > 90d) and not path ~ "/keep/"''. Fields: size, mtime, atime, ctime, btime (compared by age: 30s, 15m, 12h, 90d, 2w, 1y), path, name, relpath, ext, user, group, perm (as --perm). Operators: = != < <= > >= ~ (regex) !~ in [..], combined with and, or, not')
             [CompletionResult]::new('-w', '-w', [CompletionResultType]::ParameterName, 'Specify width of output overriding the auto detection of terminal width')
             [CompletionResult]::new('--terminal-width', '--terminal-width', [CompletionResultType]::ParameterName, 'Specify width of output overriding the auto detection of terminal width')
+            [CompletionResult]::new('--flat', '--flat', [CompletionResultType]::ParameterName, 'List the directories at the depth given by -d (default 1) with their full paths, instead of a tree. --flat=upto also lists those above it')
+            [CompletionResult]::new('--sort', '--sort', [CompletionResultType]::ParameterName, 'Order of the entries in each directory, or of the --flat listing. The entries shown are still the biggest, -r reverses the order')
             [CompletionResult]::new('-o', '-o', [CompletionResultType]::ParameterName, 'Changes output display size. si will print sizes in powers of 1000. b k m g t kb mb gb tb will print the whole tree in that size')
             [CompletionResult]::new('--output-format', '--output-format', [CompletionResultType]::ParameterName, 'Changes output display size. si will print sizes in powers of 1000. b k m g t kb mb gb tb will print the whole tree in that size')
             [CompletionResult]::new('-S', '-S ', [CompletionResultType]::ParameterName, 'Specify memory to use as stack size - use if you see: ''fatal runtime error: stack overflow'' (default low memory=1048576, high memory=1073741824)')
             [CompletionResult]::new('--stack-size', '--stack-size', [CompletionResultType]::ParameterName, 'Specify memory to use as stack size - use if you see: ''fatal runtime error: stack overflow'' (default low memory=1048576, high memory=1073741824)')
-            [CompletionResult]::new('-M', '-M ', [CompletionResultType]::ParameterName, '+/-n matches files modified more/less than n days ago , and n matches files modified exactly n days ago, days are rounded down.That is +n => (−∞, curr−(n+1)), n => [curr−(n+1), curr−n), and -n => (𝑐𝑢𝑟𝑟−𝑛, +∞)')
-            [CompletionResult]::new('--mtime', '--mtime', [CompletionResultType]::ParameterName, '+/-n matches files modified more/less than n days ago , and n matches files modified exactly n days ago, days are rounded down.That is +n => (−∞, curr−(n+1)), n => [curr−(n+1), curr−n), and -n => (𝑐𝑢𝑟𝑟−𝑛, +∞)')
+            [CompletionResult]::new('-M', '-M ', [CompletionResultType]::ParameterName, '+/-n matches files modified more/less than n days ago , and n matches files modified exactly n days ago, days are rounded down.That is +n => (−∞, curr−(n+1)), n => [curr−(n+1), curr−n), and -n => (𝑐𝑢𝑟𝑟−𝑛, +∞). Units can be given: w and y are whole days, h, m and s count from now and need a sign, eg: -6h for files modified in the last 6 hours')
+            [CompletionResult]::new('--mtime', '--mtime', [CompletionResultType]::ParameterName, '+/-n matches files modified more/less than n days ago , and n matches files modified exactly n days ago, days are rounded down.That is +n => (−∞, curr−(n+1)), n => [curr−(n+1), curr−n), and -n => (𝑐𝑢𝑟𝑟−𝑛, +∞). Units can be given: w and y are whole days, h, m and s count from now and need a sign, eg: -6h for files modified in the last 6 hours')
             [CompletionResult]::new('-A', '-A ', [CompletionResultType]::ParameterName, 'just like -mtime, but based on file access time')
             [CompletionResult]::new('--atime', '--atime', [CompletionResultType]::ParameterName, 'just like -mtime, but based on file access time')
             [CompletionResult]::new('-y', '-y', [CompletionResultType]::ParameterName, 'just like -mtime, but based on file change time')
             [CompletionResult]::new('--ctime', '--ctime', [CompletionResultType]::ParameterName, 'just like -mtime, but based on file change time')
+            [CompletionResult]::new('--btime', '--btime', [CompletionResultType]::ParameterName, 'just like -mtime, but based on file creation (birth) time. Files whose filesystem does not record it are left out')
+            [CompletionResult]::new('--newer', '--newer', [CompletionResultType]::ParameterName, 'Only include files modified after this date, eg: 2026-01-01 or "2026-09-01 12:00", or after the modification time of this file')
+            [CompletionResult]::new('--older', '--older', [CompletionResultType]::ParameterName, 'Only include files modified before this date or the modification time of this file')
             [CompletionResult]::new('--files0-from', '--files0-from', [CompletionResultType]::ParameterName, 'Read NUL-terminated paths from FILE (use `-` for stdin)')
             [CompletionResult]::new('--files-from', '--files-from', [CompletionResultType]::ParameterName, 'Read newline-terminated paths from FILE (use `-` for stdin)')
             [CompletionResult]::new('--collapse', '--collapse', [CompletionResultType]::ParameterName, 'Keep these directories collapsed')
-            [CompletionResult]::new('-m', '-m', [CompletionResultType]::ParameterName, 'Directory ''size'' is max filetime of child files instead of disk size. while a/c/m for last accessed/changed/modified time')
-            [CompletionResult]::new('--filetime', '--filetime', [CompletionResultType]::ParameterName, 'Directory ''size'' is max filetime of child files instead of disk size. while a/c/m for last accessed/changed/modified time')
+            [CompletionResult]::new('--focus', '--focus', [CompletionResultType]::ParameterName, 'Show only the tree below this path inside the scanned ones, after a line with the size of each directory above it')
+            [CompletionResult]::new('-m', '-m', [CompletionResultType]::ParameterName, 'Directory ''size'' is max filetime of child files instead of disk size. while a/c/m for last accessed/changed/modified time and b for creation time')
+            [CompletionResult]::new('--filetime', '--filetime', [CompletionResultType]::ParameterName, 'Directory ''size'' is max filetime of child files instead of disk size. while a/c/m for last accessed/changed/modified time and b for creation time')
             [CompletionResult]::new('-p', '-p', [CompletionResultType]::ParameterName, 'Subdirectories will not have their path shortened')
             [CompletionResult]::new('--full-paths', '--full-paths', [CompletionResultType]::ParameterName, 'Subdirectories will not have their path shortened')
+            [CompletionResult]::new('--gitignore', '--gitignore', [CompletionResultType]::ParameterName, 'Exclude files and directories ignored by git: .gitignore, .ignore, .git/info/exclude and the global git excludes file')
+            [CompletionResult]::new('--only-gitignored', '--only-gitignored', [CompletionResultType]::ParameterName, 'Only show files and directories ignored by git, to find large build output and other untracked content')
+            [CompletionResult]::new('--no-dustignore', '--no-dustignore', [CompletionResultType]::ParameterName, 'Do not read .dustignore files. By default the patterns of a .dustignore file exclude matching content from its directory and everything below, files above the scanned directories are not read')
             [CompletionResult]::new('-L', '-L ', [CompletionResultType]::ParameterName, 'dereference sym links - Treat sym links as directories and go into them')
             [CompletionResult]::new('--dereference-links', '--dereference-links', [CompletionResultType]::ParameterName, 'dereference sym links - Treat sym links as directories and go into them')
             [CompletionResult]::new('-x', '-x', [CompletionResultType]::ParameterName, 'Only count the files and directories on the same filesystem as the supplied directory')
             [CompletionResult]::new('--limit-filesystem', '--limit-filesystem', [CompletionResultType]::ParameterName, 'Only count the files and directories on the same filesystem as the supplied directory')
             [CompletionResult]::new('-s', '-s', [CompletionResultType]::ParameterName, 'Use file length instead of blocks')
             [CompletionResult]::new('--apparent-size', '--apparent-size', [CompletionResultType]::ParameterName, 'Use file length instead of blocks')
+            [CompletionResult]::new('--compare-sizes', '--compare-sizes', [CompletionResultType]::ParameterName, 'Show disk size and apparent size side by side with their ratio (reveals sparse and compressed files)')
+            [CompletionResult]::new('--size-difference', '--size-difference', [CompletionResultType]::ParameterName, 'Directory ''size'' is the difference between disk size and apparent size (implies --compare-sizes)')
+            [CompletionResult]::new('--deleted-open-files', '--deleted-open-files', [CompletionResultType]::ParameterName, 'Show space held by deleted files which are still open by a process (Linux only)')
+            [CompletionResult]::new('--reconcile', '--reconcile', [CompletionResultType]::ParameterName, 'Explain the difference between the total and the used space of the filesystem (as reported by df). Implies -x')
+            [CompletionResult]::new('--show-excluded', '--show-excluded', [CompletionResultType]::ParameterName, 'Print how many files and bytes each ignore option and filter left out')
+            [CompletionResult]::new('--show-remainder', '--show-remainder', [CompletionResultType]::ParameterName, 'Add a "(N more: SIZE)" entry to each directory for the entries not shown, so the shown entries add up to the directory')
             [CompletionResult]::new('-r', '-r', [CompletionResultType]::ParameterName, 'Print tree upside down (biggest highest)')
             [CompletionResult]::new('--reverse', '--reverse', [CompletionResultType]::ParameterName, 'Print tree upside down (biggest highest)')
             [CompletionResult]::new('-c', '-c', [CompletionResultType]::ParameterName, 'No colors will be printed (Useful for commands like: watch)')
@@ -70,6 +100,9 @@ Register-ArgumentCompleter -Native -CommandName 'dust' -ScriptBlock {
             [CompletionResult]::new('-C', '-C ', [CompletionResultType]::ParameterName, 'Force colors print')
             [CompletionResult]::new('--force-colors', '--force-colors', [CompletionResultType]::ParameterName, 'Force colors print')
             [CompletionResult]::new('--dim', '--dim', [CompletionResultType]::ParameterName, 'Dim the percent bars (grey) to reduce brightness on dark terminals')
+            [CompletionResult]::new('--filesystem-info', '--filesystem-info', [CompletionResultType]::ParameterName, 'Show the filesystem of each input path: mount point, type, used and free space and inode usage')
+            [CompletionResult]::new('--filesystem-percent', '--filesystem-percent', [CompletionResultType]::ParameterName, 'Show percentages of the filesystem size next to the percentages of the total')
+            [CompletionResult]::new('--percent-of-parent', '--percent-of-parent', [CompletionResultType]::ParameterName, 'Draw each bar and percentage against the parent directory instead of the total. In --flat listings the total is the parent')
             [CompletionResult]::new('-b', '-b', [CompletionResultType]::ParameterName, 'No percent bars or percentages will be displayed')
             [CompletionResult]::new('--no-percent-bars', '--no-percent-bars', [CompletionResultType]::ParameterName, 'No percent bars or percentages will be displayed')
             [CompletionResult]::new('-B', '-B ', [CompletionResultType]::ParameterName, 'percent bars moved to right side of screen')
@@ -81,6 +114,7 @@ Register-ArgumentCompleter -Native -CommandName 'dust' -ScriptBlock {
             [CompletionResult]::new('--filecount', '--filecount', [CompletionResultType]::ParameterName, 'Directory ''size'' is number of child files instead of disk size')
             [CompletionResult]::new('-i', '-i', [CompletionResultType]::ParameterName, 'Do not display hidden files')
             [CompletionResult]::new('--ignore-hidden', '--ignore-hidden', [CompletionResultType]::ParameterName, 'Do not display hidden files')
+            [CompletionResult]::new('--ignore-case', '--ignore-case', [CompletionResultType]::ParameterName, 'Match the -e, -v and -I regexes without regard to case')
             [CompletionResult]::new('-t', '-t', [CompletionResultType]::ParameterName, 'show only these file types')
             [CompletionResult]::new('--file-types', '--file-types', [CompletionResultType]::ParameterName, 'show only these file types')
             [CompletionResult]::new('-P', '-P ', [CompletionResultType]::ParameterName, 'Disable the progress indication')
@@ -4,10 +4,40 @@ complete -c dust -l config -d 'Specify a config file to use' -r -F
 complete -c dust -s n -l number-of-lines -d 'Display the \'n\' largest entries. (Default is terminal_height)' -r
 complete -c dust -s X -l ignore-directory -d 'Exclude any file or directory with this path' -r -F
 complete -c dust -s I -l ignore-all-in-file -d 'Exclude any file or directory with a regex matching that listed in this file, the file entries will be added to the ignore regexs provided by --invert_filter' -r -F
-complete -c dust -s z -l min-size -d 'Minimum size file to include in output' -r
+complete -c dust -l exclude -d 'Exclude any file or directory matching this glob, eg: "**/node_modules", "*.o" or "build/*/cache". Globs match the last components of a path below the scanned directory, a leading / anchors them to it and a trailing / only matches directories' -r
+complete -c dust -l exclude-from -d 'Exclude any file or directory matching a glob listed in this file, one per line. Lines starting with # are comments' -r -F
+complete -c dust -l exclude-fstype -d 'Exclude filesystems of this type, eg: nfs,fuse.sshfs. Virtual filesystems like proc and sysfs are then excluded too unless included (Linux only)' -r
+complete -c dust -l include-fstype -d 'Only include filesystems of this type, eg: ext4,btrfs (Linux only)' -r
+complete -c dust -l columns -d 'Columns to show for each entry in place of the size, eg: disk,files,newest. The entries shown, their order and the bars still go by the size' -r -f -a "disk\t'disk size'
+apparent\t'apparent size'
+files\t'number of files below'
+dirs\t'number of directories below, counting the directory itself'
+newest\t'the most recent modification time below'
+oldest\t'the oldest modification time below'"
+complete -c dust -l heatmap -d 'Color the bars, or the names in place of LS_COLORS, by the age of the newest file below: from green (this week) to red (over 2 years)' -r -f -a "bar\t'the percent bars, names keep their LS_COLORS'
+name\t'the names'"
+complete -c dust -l per-dir -d 'Show at most this many entries in each directory, down to --depth, instead of the biggest entries of the whole tree. The number of lines (-n) does not apply' -r
+complete -c dust -l min-parent-percent -d 'Only show entries taking at least this percent of their directory, the same as -z PERCENT%p' -r
+complete -c dust -s z -l min-size -d 'Minimum size file to include in output, eg: 10M, 1% of the total or 5%p of the parent directory. Percents can not be used with filetime' -r
+complete -c dust -l max-size -d 'Only count files up to this size, eg: 4K. Unlike min-size this applies to each file so totals only include the smaller files' -r
+complete -c dust -l size-range -d 'Only count files with a size in this range, eg: 4K..1M, ..4K or 1G..' -r
+complete -c dust -l user -d 'Only count files owned by this user, a name or a uid. Can be repeated' -r
+complete -c dust -l group -d 'Only count files owned by this group, a name or a gid. Can be repeated' -r
+complete -c dust -l perm -d 'Only count files with these permission bits, as with find: 644 exactly, -4000 or -u+s for all of the bits (setuid), /o+w for any of them (world-writable)' -r
 complete -c dust -s v -l invert-filter -d 'Exclude filepaths matching this regex. To ignore png files type: -v "\\.png$"' -r
 complete -c dust -s e -l filter -d 'Only include filepaths matching this regex. For png files type: -e "\\.png$"' -r
+complete -c dust -l match-on -d 'What the -e, -v and -I regexes are matched against: the whole path, the file name alone or the path relative to the directory being scanned' -r -f -a "path\t'the path as walked, including the directory given to dust'
+name\t'the file or directory name alone'
+relative\t'the path below the directory given to dust'"
+complete -c dust -l where -d 'Only include files matching this expression, eg: \'size > 100M and (ext in [log, gz] or mtime > 90d) and not path ~ "/keep/"\'. Fields: size, mtime, atime, ctime, btime (compared by age: 30s, 15m, 12h, 90d, 2w, 1y), path, name, relpath, ext, user, group, perm (as --perm). Operators: = != < <= > >= ~ (regex) !~ in [..], combined with and, or, not' -r
 complete -c dust -s w -l terminal-width -d 'Specify width of output overriding the auto detection of terminal width' -r
+complete -c dust -l flat -d 'List the directories at the depth given by -d (default 1) with their full paths, instead of a tree. --flat=upto also lists those above it' -r -f -a "exact\t'only the directories at exactly that depth'
+upto\t'every directory down to that depth, as du -d'"
+complete -c dust -l sort -d 'Order of the entries in each directory, or of the --flat listing. The entries shown are still the biggest, -r reverses the order' -r -f -a "size\t'biggest last'
+name\t'by name, with numbers in order: file2 before file10'
+mtime\t'the most recently modified file below last'
+count\t'the most files below last'
+extension\t'by extension, then by name'"
 complete -c dust -s o -l output-format -d 'Changes output display size. si will print sizes in powers of 1000. b k m g t kb mb gb tb will print the whole tree in that size' -r -f -a "si\t'SI prefix (powers of 1000)'
 b\t'byte (B)'
 k\t'kibibyte (KiB)'
@@ -19,29 +49,47 @@ mb\t'megabyte (MB)'
 gb\t'gigabyte (GB)'
 tb\t'terabyte (TB)'"
 complete -c dust -s S -l stack-size -d 'Specify memory to use as stack size - use if you see: \'fatal runtime error: stack overflow\' (default low memory=1048576, high memory=1073741824)' -r
-complete -c dust -s M -l mtime -d '+/-n matches files modified more/less than n days ago , and n matches files modified exactly n days ago, days are rounded down.That is +n => (−∞, curr−(n+1)), n => [curr−(n+1), curr−n), and -n => (𝑐𝑢𝑟𝑟−𝑛, +∞)' -r
+complete -c dust -s M -l mtime -d '+/-n matches files modified more/less than n days ago , and n matches files modified exactly n days ago, days are rounded down.That is +n => (−∞, curr−(n+1)), n => [curr−(n+1), curr−n), and -n => (𝑐𝑢𝑟𝑟−𝑛, +∞). Units can be given: w and y are whole days, h, m and s count from now and need a sign, eg: -6h for files modified in the last 6 hours' -r
 complete -c dust -s A -l atime -d 'just like -mtime, but based on file access time' -r
 complete -c dust -s y -l ctime -d 'just like -mtime, but based on file change time' -r
+complete -c dust -l btime -d 'just like -mtime, but based on file creation (birth) time. Files whose filesystem does not record it are left out' -r
+complete -c dust -l newer -d 'Only include files modified after this date, eg: 2026-01-01 or "2026-09-01 12:00", or after the modification time of this file' -r -F
+complete -c dust -l older -d 'Only include files modified before this date or the modification time of this file' -r -F
 complete -c dust -l files0-from -d 'Read NUL-terminated paths from FILE (use `-` for stdin)' -r -F
 complete -c dust -l files-from -d 'Read newline-terminated paths from FILE (use `-` for stdin)' -r -F
 complete -c dust -l collapse -d 'Keep these directories collapsed' -r -F
-complete -c dust -s m -l filetime -d 'Directory \'size\' is max filetime of child files instead of disk size. while a/c/m for last accessed/changed/modified time' -r -f -a "a\t'last accessed time'
+complete -c dust -l focus -d 'Show only the tree below this path inside the scanned ones, after a line with the size of each directory above it' -r -F
+complete -c dust -s m -l filetime -d 'Directory \'size\' is max filetime of child files instead of disk size. while a/c/m for last accessed/changed/modified time and b for creation time' -r -f -a "a\t'last accessed time'
 c\t'last changed time'
-m\t'last modified time'"
+m\t'last modified time'
+b\t'creation (birth) time, where the filesystem records it'"
 complete -c dust -s p -l full-paths -d 'Subdirectories will not have their path shortened'
+complete -c dust -l gitignore -d 'Exclude files and directories ignored by git: .gitignore, .ignore, .git/info/exclude and the global git excludes file'
+complete -c dust -l only-gitignored -d 'Only show files and directories ignored by git, to find large build output and other untracked content'
+complete -c dust -l no-dustignore -d 'Do not read .dustignore files. By default the patterns of a .dustignore file exclude matching content from its directory and everything below, files above the scanned directories are not read'
 complete -c dust -s L -l dereference-links -d 'dereference sym links - Treat sym links as directories and go into them'
 complete -c dust -s x -l limit-filesystem -d 'Only count the files and directories on the same filesystem as the supplied directory'
 complete -c dust -s s -l apparent-size -d 'Use file length instead of blocks'
+complete -c dust -l compare-sizes -d 'Show disk size and apparent size side by side with their ratio (reveals sparse and compressed files)'
+complete -c dust -l size-difference -d 'Directory \'size\' is the difference between disk size and apparent size (implies --compare-sizes)'
+complete -c dust -l deleted-open-files -d 'Show space held by deleted files which are still open by a process (Linux only)'
+complete -c dust -l reconcile -d 'Explain the difference between the total and the used space of the filesystem (as reported by df). Implies -x'
+complete -c dust -l show-excluded -d 'Print how many files and bytes each ignore option and filter left out'
+complete -c dust -l show-remainder -d 'Add a "(N more: SIZE)" entry to each directory for the entries not shown, so the shown entries add up to the directory'
 complete -c dust -s r -l reverse -d 'Print tree upside down (biggest highest)'
 complete -c dust -s c -l no-colors -d 'No colors will be printed (Useful for commands like: watch)'
 complete -c dust -s C -l force-colors -d 'Force colors print'
 complete -c dust -l dim -d 'Dim the percent bars (grey) to reduce brightness on dark terminals'
+complete -c dust -l filesystem-info -d 'Show the filesystem of each input path: mount point, type, used and free space and inode usage'
+complete -c dust -l filesystem-percent -d 'Show percentages of the filesystem size next to the percentages of the total'
+complete -c dust -l percent-of-parent -d 'Draw each bar and percentage against the parent directory instead of the total. In --flat listings the total is the parent'
 complete -c dust -s b -l no-percent-bars -d 'No percent bars or percentages will be displayed'
 complete -c dust -s B -l bars-on-right -d 'percent bars moved to right side of screen'
 complete -c dust -s R -l screen-reader -d 'For screen readers. Removes bars. Adds new column: depth level (May want to use -p too for full path)'
 complete -c dust -l skip-total -d 'No total row will be displayed'
 complete -c dust -s f -l filecount -d 'Directory \'size\' is number of child files instead of disk size'
 complete -c dust -s i -l ignore-hidden -d 'Do not display hidden files'
+complete -c dust -l ignore-case -d 'Match the -e, -v and -I regexes without regard to case'
 complete -c dust -s t -l file-types -d 'show only these file types'
 complete -c dust -s P -l no-progress -d 'Disable the progress indication'
 complete -c dust -l print-errors -d 'Print path with errors'
@@ -39,6 +39,34 @@ pub struct Cli {
     #[arg(short('I'), long, value_name("FILE"), value_hint(ValueHint::FilePath))]
     pub ignore_all_in_file: Option<String>,
 
+    /// Exclude any file or directory matching this glob, eg: "**/node_modules",
+    /// "*.o" or "build/*/cache". Globs match the last components of a path
+    /// below the scanned directory, a leading / anchors them to it and a
+    /// trailing / only matches directories
+    #[arg(long, value_name("GLOB"))]
+    pub exclude: Option<Vec<String>>,
+
+    /// Exclude any file or directory matching a glob listed in this file, one
+    /// per line. Lines starting with # are comments
+    #[arg(long, value_name("FILE"), value_hint(ValueHint::FilePath))]
+    pub exclude_from: Option<String>,
+
+    /// Exclude files and directories ignored by git: .gitignore, .ignore,
+    /// .git/info/exclude and the global git excludes file
+    #[arg(long, conflicts_with("only_gitignored"))]
+    pub gitignore: bool,
+
+    /// Only show files and directories ignored by git, to find large build
+    /// output and other untracked content
+    #[arg(long)]
+    pub only_gitignored: bool,
+
+    /// Do not read .dustignore files. By default the patterns of a .dustignore
+    /// file exclude matching content from its directory and everything below,
+    /// files above the scanned directories are not read
+    #[arg(long)]
+    pub no_dustignore: bool,
+
     /// dereference sym links - Treat sym links as directories and go into them
     #[arg(short('L'), long)]
     pub dereference_links: bool,
@@ -48,10 +76,70 @@ pub struct Cli {
     #[arg(short('x'), long)]
     pub limit_filesystem: bool,
 
+    /// Exclude filesystems of this type, eg: nfs,fuse.sshfs. Virtual filesystems
+    /// like proc and sysfs are then excluded too unless included (Linux only)
+    #[arg(long, value_name("TYPE"), value_delimiter(','))]
+    pub exclude_fstype: Option<Vec<String>>,
+
+    /// Only include filesystems of this type, eg: ext4,btrfs (Linux only)
+    #[arg(
+        long,
+        value_name("TYPE"),
+        value_delimiter(','),
+        conflicts_with("limit_filesystem")
+    )]
+    pub include_fstype: Option<Vec<String>>,
+
     /// Use file length instead of blocks
     #[arg(short('s'), long)]
     pub apparent_size: bool,
 
+    /// Show disk size and apparent size side by side with their ratio (reveals
+    /// sparse and compressed files)
+    #[arg(long, conflicts_with("filecount"), conflicts_with("filetime"))]
+    pub compare_sizes: bool,
+
+    /// Columns to show for each entry in place of the size, eg: disk,files,newest.
+    /// The entries shown, their order and the bars still go by the size
+    #[arg(
+        long,
+        value_enum,
+        value_delimiter(','),
+        value_name("COLUMN"),
+        conflicts_with("compare_sizes"),
+        conflicts_with("size_difference")
+    )]
+    pub columns: Option<Vec<Column>>,
+
+    /// Directory 'size' is the difference between disk size and apparent size
+    /// (implies --compare-sizes)
+    #[arg(long, conflicts_with("filecount"), conflicts_with("filetime"))]
+    pub size_difference: bool,
+
+    /// Show space held by deleted files which are still open by a process
+    /// (Linux only)
+    #[arg(
+        long,
+        conflicts_with("filecount"),
+        conflicts_with("filetime"),
+        conflicts_with("file_types")
+    )]
+    pub deleted_open_files: bool,
+
+    /// Explain the difference between the total and the used space of the
+    /// filesystem (as reported by df). Implies -x
+    #[arg(long)]
+    pub reconcile: bool,
+
+    /// Print how many files and bytes each ignore option and filter left out
+    #[arg(long)]
+    pub show_excluded: bool,
+
+    /// Add a "(N more: SIZE)" entry to each directory for the entries not shown, so
+    /// the shown entries add up to the directory
+    #[arg(long)]
+    pub show_remainder: bool,
+
     /// Print tree upside down (biggest highest)
     #[arg(short, long)]
     pub reverse: bool,
@@ -68,6 +156,33 @@ pub struct Cli {
     #[arg(long)]
     pub dim: bool,
 
+    /// Color the bars, or the names in place of LS_COLORS, by the age of the
+    /// newest file below: from green (this week) to red (over 2 years)
+    #[arg(
+        long,
+        value_enum,
+        value_name("TARGET"),
+        num_args(0..=1),
+        require_equals(true),
+        default_missing_value("bar")
+    )]
+    pub heatmap: Option<Heatmap>,
+
+    /// Show the filesystem of each input path: mount point, type, used and free
+    /// space and inode usage
+    #[arg(long)]
+    pub filesystem_info: bool,
+
+    /// Show percentages of the filesystem size next to the percentages of the
+    /// total
+    #[arg(long, conflicts_with("filecount"), conflicts_with("filetime"))]
+    pub filesystem_percent: bool,
+
+    /// Draw each bar and percentage against the parent directory instead of the
+    /// total. In --flat listings the total is the parent
+    #[arg(long, conflicts_with("filetime"))]
+    pub percent_of_parent: bool,
+
     /// No percent bars or percentages will be displayed
     #[arg(short('b'), long)]
     pub no_percent_bars: bool,
@@ -76,10 +191,50 @@ pub struct Cli {
     #[arg(short('B'), long)]
     pub bars_on_right: bool,
 
-    /// Minimum size file to include in output
+    /// Show at most this many entries in each directory, down to --depth,
+    /// instead of the biggest entries of the whole tree. The number of lines
+    /// (-n) does not apply
+    #[arg(long, value_name("K"))]
+    pub per_dir: Option<usize>,
+
+    /// Only show entries taking at least this percent of their directory, the
+    /// same as -z PERCENT%p
+    #[arg(
+        long,
+        value_name("PERCENT"),
+        conflicts_with("filetime"),
+        conflicts_with("min_size")
+    )]
+    pub min_parent_percent: Option<f64>,
+
+    /// Minimum size file to include in output, eg: 10M, 1% of the total or 5%p
+    /// of the parent directory. Percents can not be used with filetime
     #[arg(short('z'), long)]
     pub min_size: Option<String>,
 
+    /// Only count files up to this size, eg: 4K. Unlike min-size this applies
+    /// to each file so totals only include the smaller files
+    #[arg(long, value_name("SIZE"))]
+    pub max_size: Option<String>,
+
+    /// Only count files with a size in this range, eg: 4K..1M, ..4K or 1G..
+    #[arg(long, value_name("MIN..MAX"))]
+    pub size_range: Option<String>,
+
+    /// Only count files owned by this user, a name or a uid. Can be repeated
+    #[arg(long, value_name("USER"))]
+    pub user: Option<Vec<String>>,
+
+    /// Only count files owned by this group, a name or a gid. Can be repeated
+    #[arg(long, value_name("GROUP"))]
+    pub group: Option<Vec<String>>,
+
+    /// Only count files with these permission bits, as with find: 644 exactly,
+    /// -4000 or -u+s for all of the bits (setuid), /o+w for any of them
+    /// (world-writable)
+    #[arg(long, value_name("MODE"), allow_hyphen_values(true))]
+    pub perm: Option<String>,
+
     /// For screen readers. Removes bars. Adds new column: depth level (May want
     /// to use -p too for full path)
     #[arg(short('R'), long)]
@@ -114,6 +269,23 @@ pub struct Cli {
     #[arg(short('e'), long, value_name("REGEX"), conflicts_with("file_types"))]
     pub filter: Option<Vec<String>>,
 
+    /// What the -e, -v and -I regexes are matched against: the whole path, the
+    /// file name alone or the path relative to the directory being scanned
+    #[arg(long, value_enum, value_name("PART"), default_value_t = MatchOn::Path)]
+    pub match_on: MatchOn,
+
+    /// Match the -e, -v and -I regexes without regard to case
+    #[arg(long)]
+    pub ignore_case: bool,
+
+    /// Only include files matching this expression, eg: 'size > 100M and (ext
+    /// in [log, gz] or mtime > 90d) and not path ~ "/keep/"'. Fields: size, mtime,
+    /// atime, ctime, btime (compared by age: 30s, 15m, 12h, 90d, 2w, 1y), path, name,
+    /// relpath, ext, user, group, perm (as --perm).
+    /// Operators: = != < <= > >= ~ (regex) !~ in [..], combined with and, or, not
+    #[arg(long("where"), value_name("EXPR"))]
+    pub filter_where: Option<String>,
+
     /// show only these file types
     #[arg(short('t'), long, conflicts_with("depth"), conflicts_with("only_dir"))]
     pub file_types: bool,
@@ -143,6 +315,25 @@ pub struct Cli {
     #[arg(short('F'), long, conflicts_with("only_dir"))]
     pub only_file: bool,
 
+    /// List the directories at the depth given by -d (default 1) with their
+    /// full paths, instead of a tree. --flat=upto also lists those above it
+    #[arg(
+        long,
+        value_enum,
+        value_name("DEPTH"),
+        num_args(0..=1),
+        require_equals(true),
+        default_missing_value("exact"),
+        conflicts_with("only_file"),
+        conflicts_with("file_types")
+    )]
+    pub flat: Option<FlatDepth>,
+
+    /// Order of the entries in each directory, or of the --flat listing. The
+    /// entries shown are still the biggest, -r reverses the order
+    #[arg(long, value_enum, default_value_t = SortBy::Size)]
+    pub sort: SortBy,
+
     /// Changes output display size. si will print sizes in powers of 1000. b k
     /// m g t kb mb gb tb will print the whole tree in that size.
     #[arg(short, long, value_enum, value_name("FORMAT"), ignore_case(true))]
@@ -164,7 +355,9 @@ pub struct Cli {
 
     /// +/-n matches files modified more/less than n days ago , and n matches
     /// files modified exactly n days ago, days are rounded down.That is +n =>
-    /// (−∞, curr−(n+1)), n => [curr−(n+1), curr−n), and -n => (𝑐𝑢𝑟𝑟−𝑛, +∞)
+    /// (−∞, curr−(n+1)), n => [curr−(n+1), curr−n), and -n => (𝑐𝑢𝑟𝑟−𝑛, +∞).
+    /// Units can be given: w and y are whole days, h, m and s count from now
+    /// and need a sign, eg: -6h for files modified in the last 6 hours
     #[arg(short('M'), long, allow_hyphen_values(true))]
     pub mtime: Option<String>,
 
@@ -176,6 +369,21 @@ pub struct Cli {
     #[arg(short('y'), long, allow_hyphen_values(true))]
     pub ctime: Option<String>,
 
+    /// just like -mtime, but based on file creation (birth) time. Files whose
+    /// filesystem does not record it are left out
+    #[arg(long, allow_hyphen_values(true))]
+    pub btime: Option<String>,
+
+    /// Only include files modified after this date, eg: 2026-01-01 or
+    /// "2026-09-01 12:00", or after the modification time of this file
+    #[arg(long, value_name("DATE|FILE"), value_hint(ValueHint::AnyPath))]
+    pub newer: Option<String>,
+
+    /// Only include files modified before this date or the modification time of
+    /// this file
+    #[arg(long, value_name("DATE|FILE"), value_hint(ValueHint::AnyPath))]
+    pub older: Option<String>,
+
     /// Read NUL-terminated paths from FILE (use `-` for stdin).
     #[arg(long, value_hint(ValueHint::AnyPath), conflicts_with("files_from"))]
     pub files0_from: Option<String>,
@@ -188,8 +396,13 @@ pub struct Cli {
     #[arg(long, value_hint(ValueHint::AnyPath))]
     pub collapse: Option<Vec<String>>,
 
+    /// Show only the tree below this path inside the scanned ones, after a line
+    /// with the size of each directory above it
+    #[arg(long, value_name("PATH"), value_hint(ValueHint::AnyPath))]
+    pub focus: Option<String>,
+
     /// Directory 'size' is max filetime of child files instead of disk size.
-    /// while a/c/m for last accessed/changed/modified time
+    /// while a/c/m for last accessed/changed/modified time and b for creation time
     #[arg(short('m'), long, value_enum)]
     pub filetime: Option<FileTime>,
 }
@@ -249,6 +462,75 @@ impl fmt::Display for OutputFormat {
     }
 }
 
+#[derive(Clone, Copy, Debug, PartialEq, ValueEnum)]
+pub enum MatchOn {
+    /// the path as walked, including the directory given to dust
+    Path,
+
+    /// the file or directory name alone
+    Name,
+
+    /// the path below the directory given to dust
+    Relative,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, ValueEnum)]
+pub enum Heatmap {
+    /// the percent bars, names keep their LS_COLORS
+    Bar,
+
+    /// the names
+    Name,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, ValueEnum)]
+pub enum Column {
+    /// disk size
+    Disk,
+
+    /// apparent size
+    Apparent,
+
+    /// number of files below
+    Files,
+
+    /// number of directories below, counting the directory itself
+    Dirs,
+
+    /// the most recent modification time below
+    Newest,
+
+    /// the oldest modification time below
+    Oldest,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, ValueEnum)]
+pub enum FlatDepth {
+    /// only the directories at exactly that depth
+    Exact,
+
+    /// every directory down to that depth, as du -d
+    Upto,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, ValueEnum)]
+pub enum SortBy {
+    /// biggest last
+    Size,
+
+    /// by name, with numbers in order: file2 before file10
+    Name,
+
+    /// the most recently modified file below last
+    Mtime,
+
+    /// the most files below last
+    Count,
+
+    /// by extension, then by name
+    Extension,
+}
+
 #[derive(Clone, Copy, Debug, ValueEnum)]
 pub enum FileTime {
     /// last accessed time
@@ -262,4 +544,8 @@ pub enum FileTime {
     /// last modified time
     #[value(name = "m", alias("modified"))]
     Modified,
+
+    /// creation (birth) time, where the filesystem records it
+    #[value(name = "b", alias("created"), alias("birth"))]
+    Created,
 }
@@ -1,5 +1,7 @@
+use crate::ignore_rules::GitIgnoreMode;
 use crate::node::FileTime;
 use chrono::{Local, TimeZone};
+use clap::ValueEnum;
 use config_file::FromConfigFile;
 use regex::Regex;
 use serde::Deserialize;
@@ -8,8 +10,19 @@ use std::path::PathBuf;
 use std::process;
 
 use crate::cli::Cli;
+use crate::cli::Column;
+use crate::cli::Heatmap;
 use crate::dir_walker::Operator;
 use crate::display::get_number_format;
+use crate::filter::MinSize;
+use crate::owner_filter::ModeMatch;
+use crate::owner_filter::parse_group;
+use crate::owner_filter::parse_mode;
+use crate::owner_filter::parse_user;
+use crate::theme::Theme;
+use crate::theme::ThemeConfig;
+use crate::time_filter::parse_time_filter;
+use crate::time_filter::parse_time_point;
 
 pub static DAY_SECONDS: i64 = 24 * 60 * 60;
 
@@ -18,17 +31,37 @@ pub static DAY_SECONDS: i64 = 24 * 60 * 60;
 pub struct Config {
     pub display_full_paths: Option<bool>,
     pub display_apparent_size: Option<bool>,
+    pub compare_sizes: Option<bool>,
+    pub columns: Option<Vec<String>>,
+    pub deleted_open_files: Option<bool>,
+    pub reconcile: Option<bool>,
+    pub show_excluded: Option<bool>,
+    pub show_remainder: Option<bool>,
     pub reverse: Option<bool>,
     pub no_colors: Option<bool>,
     pub force_colors: Option<bool>,
     pub dim: Option<bool>,
+    pub heatmap: Option<String>,
     pub no_bars: Option<bool>,
+    pub filesystem_info: Option<bool>,
+    pub filesystem_percent: Option<bool>,
+    pub percent_of_parent: Option<bool>,
     pub skip_total: Option<bool>,
     pub screen_reader: Option<bool>,
     pub ignore_hidden: Option<bool>,
+    pub ignore_case: Option<bool>,
+    pub gitignore: Option<bool>,
+    pub only_gitignored: Option<bool>,
+    pub no_dustignore: Option<bool>,
     pub limit_filesystem: Option<bool>,
+    pub exclude_fstype: Option<Vec<String>>,
+    pub include_fstype: Option<Vec<String>>,
     pub output_format: Option<String>,
     pub min_size: Option<String>,
+    pub per_dir: Option<usize>,
+    pub min_parent_percent: Option<f64>,
+    pub max_size: Option<String>,
+    pub size_range: Option<String>,
     pub only_dir: Option<bool>,
     pub only_file: Option<bool>,
     pub disable_progress: Option<bool>,
@@ -42,6 +75,8 @@ pub struct Config {
     pub number_of_lines: Option<usize>,
     pub files_from: Option<String>,
     pub collapse: Option<Vec<String>>,
+    pub exclude: Option<Vec<String>>,
+    pub theme: Option<ThemeConfig>,
 }
 
 impl Config {
@@ -72,11 +107,73 @@ impl Config {
     pub fn get_apparent_size(&self, options: &Cli) -> bool {
         Some(true) == self.display_apparent_size || options.apparent_size
     }
+    pub fn get_compare_sizes(&self, options: &Cli) -> bool {
+        Some(true) == self.compare_sizes || options.compare_sizes || options.size_difference
+    }
+    pub fn get_columns(&self, options: &Cli) -> Vec<Column> {
+        // command line wins, as in get_collapse
+        if let Some(ref columns) = options.columns {
+            return columns.clone();
+        }
+        self.columns
+            .iter()
+            .flatten()
+            .filter_map(|name| {
+                let column = Column::from_str(name, true).ok();
+                if column.is_none() {
+                    eprintln!("Ignoring unknown column: {name}");
+                }
+                column
+            })
+            .collect()
+    }
+    pub fn get_theme(&self) -> Theme {
+        self.theme
+            .as_ref()
+            .map(Theme::from_config)
+            .unwrap_or_default()
+    }
+    pub fn get_deleted_open_files(&self, options: &Cli) -> bool {
+        Some(true) == self.deleted_open_files || options.deleted_open_files
+    }
+    pub fn get_reconcile(&self, options: &Cli) -> bool {
+        Some(true) == self.reconcile || options.reconcile
+    }
+    pub fn get_show_excluded(&self, options: &Cli) -> bool {
+        Some(true) == self.show_excluded || options.show_excluded
+    }
+    pub fn get_show_remainder(&self, options: &Cli) -> bool {
+        Some(true) == self.show_remainder || options.show_remainder
+    }
     pub fn get_ignore_hidden(&self, options: &Cli) -> bool {
         Some(true) == self.ignore_hidden || options.ignore_hidden
     }
+    pub fn get_ignore_case(&self, options: &Cli) -> bool {
+        Some(true) == self.ignore_case || options.ignore_case
+    }
+
+    pub fn get_gitignore(&self, options: &Cli) -> Option<GitIgnoreMode> {
+        // The flags win over the config file, they can not both be set
+        if options.only_gitignored {
+            Some(GitIgnoreMode::OnlyIgnored)
+        } else if options.gitignore {
+            Some(GitIgnoreMode::Skip)
+        } else if Some(true) == self.only_gitignored {
+            Some(GitIgnoreMode::OnlyIgnored)
+        } else if Some(true) == self.gitignore {
+            Some(GitIgnoreMode::Skip)
+        } else {
+            None
+        }
+    }
+    pub fn get_use_dustignore(&self, options: &Cli) -> bool {
+        Some(true) != self.no_dustignore && !options.no_dustignore
+    }
     pub fn get_limit_filesystem(&self, options: &Cli) -> bool {
-        Some(true) == self.limit_filesystem || options.limit_filesystem
+        // Comparing with the used space of a filesystem only makes sense within it
+        Some(true) == self.limit_filesystem
+            || options.limit_filesystem
+            || self.get_reconcile(options)
     }
     pub fn get_full_paths(&self, options: &Cli) -> bool {
         Some(true) == self.display_full_paths || options.full_paths
@@ -87,6 +184,15 @@ impl Config {
     pub fn get_no_bars(&self, options: &Cli) -> bool {
         Some(true) == self.no_bars || options.no_percent_bars
     }
+    pub fn get_filesystem_info(&self, options: &Cli) -> bool {
+        Some(true) == self.filesystem_info || options.filesystem_info
+    }
+    pub fn get_filesystem_percent(&self, options: &Cli) -> bool {
+        Some(true) == self.filesystem_percent || options.filesystem_percent
+    }
+    pub fn get_percent_of_parent(&self, options: &Cli) -> bool {
+        Some(true) == self.percent_of_parent || options.percent_of_parent
+    }
     pub fn get_output_format(&self, options: &Cli) -> String {
         let out_fmt = options.output_format;
         (match out_fmt {
@@ -116,21 +222,66 @@ impl Config {
 
         self.depth.unwrap_or(usize::MAX)
     }
-    pub fn get_min_size(&self, options: &Cli) -> Option<usize> {
+    pub fn get_per_dir(&self, options: &Cli) -> Option<usize> {
+        options.per_dir.or(self.per_dir)
+    }
+    pub fn get_min_size(&self, options: &Cli) -> Option<MinSize> {
         let size_from_param = options.min_size.as_ref();
-        self._get_min_size(size_from_param)
+        // --min-parent-percent N is the same as -z N%p
+        let min_size = match options.min_parent_percent {
+            Some(percent) => Some(MinSize::PercentOfParent(percent)),
+            None => self
+                ._get_min_size(size_from_param)
+                .or_else(|| self.min_parent_percent.map(MinSize::PercentOfParent)),
+        };
+        // With -m the sizes are times, a percent of them means nothing
+        if self.get_filetime(options).is_some()
+            && matches!(
+                min_size,
+                Some(MinSize::PercentOfTotal(_) | MinSize::PercentOfParent(_))
+            )
+        {
+            eprintln!("A percent min-size can not be used with filetime");
+            process::exit(1)
+        }
+        min_size
     }
-    fn _get_min_size(&self, min_size: Option<&String>) -> Option<usize> {
-        let size_from_param = min_size.and_then(|a| convert_min_size(a));
+    fn _get_min_size(&self, min_size: Option<&String>) -> Option<MinSize> {
+        let size_from_param = min_size.and_then(|a| parse_min_size(a));
 
         if size_from_param.is_none() {
             self.min_size
                 .as_ref()
-                .and_then(|a| convert_min_size(a.as_ref()))
+                .and_then(|a| parse_min_size(a.as_ref()))
         } else {
             size_from_param
         }
     }
+    // Inclusive (min, max) file sizes from --size-range and --max-size. Unlike
+    // min-size these are applied to each file while walking so totals only
+    // include files in the range
+    pub fn get_size_range(&self, options: &Cli) -> Option<(Option<u64>, Option<u64>)> {
+        let size_range = options.size_range.as_ref().or(self.size_range.as_ref());
+        let max_size = options.max_size.as_ref().or(self.max_size.as_ref());
+        let (min, max) = match size_range {
+            Some(range) => parse_size_range(range).unwrap_or_else(|| {
+                eprintln!("Invalid value for size range: {range:?}, eg: 4K..1M");
+                process::exit(1)
+            }),
+            None => (None, None),
+        };
+        let max = match max_size {
+            Some(size) => {
+                let size = convert_size(size).unwrap_or_else(|| {
+                    eprintln!("Invalid value for max-size: {size:?}");
+                    process::exit(1)
+                }) as u64;
+                Some(max.map_or(size, |m: u64| m.min(size)))
+            }
+            None => max,
+        };
+        (min.is_some() || max.is_some()).then_some((min, max))
+    }
     pub fn get_only_dir(&self, options: &Cli) -> bool {
         Some(true) == self.only_dir || options.only_dir
     }
@@ -147,6 +298,16 @@ impl Config {
     pub fn get_dim(&self, options: &Cli) -> bool {
         Some(true) == self.dim || options.dim
     }
+    pub fn get_heatmap(&self, options: &Cli) -> Option<Heatmap> {
+        options.heatmap.or_else(|| {
+            let name = self.heatmap.as_ref()?;
+            let heatmap = Heatmap::from_str(name, true).ok();
+            if heatmap.is_none() {
+                eprintln!("Ignoring unknown heatmap: {name}");
+            }
+            heatmap
+        })
+    }
     pub fn get_custom_stack_size(&self, options: &Cli) -> Option<usize> {
         let from_cmd_line = options.stack_size;
         if from_cmd_line.is_none() {
@@ -176,6 +337,24 @@ impl Config {
         }
     }
 
+    // Owners of the files to include, names are resolved to ids on this machine
+    pub fn get_users(&self, options: &Cli) -> Vec<u32> {
+        get_owner_ids(options.user.as_ref(), "user", parse_user)
+    }
+
+    pub fn get_groups(&self, options: &Cli) -> Vec<u32> {
+        get_owner_ids(options.group.as_ref(), "group", parse_group)
+    }
+
+    pub fn get_perm(&self, options: &Cli) -> Option<ModeMatch> {
+        options.perm.as_ref().map(|perm| {
+            parse_mode(perm).unwrap_or_else(|| {
+                eprintln!("Invalid value for permission bits: {perm:?}");
+                process::exit(1)
+            })
+        })
+    }
+
     pub fn get_modified_time_operator(&self, options: &Cli) -> Option<(Operator, i64)> {
         get_filter_time_operator(options.mtime.as_ref(), get_current_date_epoch_seconds())
     }
@@ -188,6 +367,44 @@ impl Config {
         get_filter_time_operator(options.ctime.as_ref(), get_current_date_epoch_seconds())
     }
 
+    pub fn get_created_time_operator(&self, options: &Cli) -> Option<(Operator, i64)> {
+        get_filter_time_operator(options.btime.as_ref(), get_current_date_epoch_seconds())
+    }
+
+    // Modification times bounding the files to include, from a date or a reference file
+    pub fn get_newer(&self, options: &Cli) -> Option<i64> {
+        get_time_point(options.newer.as_ref())
+    }
+
+    pub fn get_older(&self, options: &Cli) -> Option<i64> {
+        get_time_point(options.older.as_ref())
+    }
+
+    pub fn get_exclude_fstype(&self, options: &Cli) -> Vec<String> {
+        // command line wins, as in get_collapse
+        match &options.exclude_fstype {
+            Some(types) => types.clone(),
+            None => self.exclude_fstype.clone().unwrap_or_default(),
+        }
+    }
+
+    pub fn get_include_fstype(&self, options: &Cli) -> Vec<String> {
+        match &options.include_fstype {
+            Some(types) => types.clone(),
+            None => self.include_fstype.clone().unwrap_or_default(),
+        }
+    }
+
+    pub fn get_exclude(&self, options: &Cli) -> Vec<String> {
+        // Globs from the config file and the command line are all used
+        self.exclude
+            .iter()
+            .chain(options.exclude.iter())
+            .flatten()
+            .cloned()
+            .collect()
+    }
+
     pub fn get_collapse(&self, options: &Cli) -> Option<Vec<String>> {
         // command line wins, as in get_threads and get_number_of_lines
         if options.collapse.is_none() {
@@ -214,29 +431,74 @@ fn get_filter_time_operator(
     option_value: Option<&String>,
     current_date_epoch_seconds: i64,
 ) -> Option<(Operator, i64)> {
-    match option_value {
-        Some(val) => {
-            let days = val.parse::<i64>().unwrap_or_else(|_| {
+    option_value.map(|val| {
+        parse_time_filter(val, Local::now().timestamp(), current_date_epoch_seconds).unwrap_or_else(
+            || {
                 eprintln!("Invalid value for time filter: {val:?}");
                 process::exit(1)
-            });
-            let time = current_date_epoch_seconds - days.abs() * DAY_SECONDS;
-            // the parse above rejects an empty string, so there is a first char
-            match val.chars().next().unwrap_or_else(|| {
-                eprintln!("Invalid value for time filter: {val:?}");
+            },
+        )
+    })
+}
+
+fn get_owner_ids(
+    option_value: Option<&Vec<String>>,
+    kind: &str,
+    parse: fn(&str) -> Option<u32>,
+) -> Vec<u32> {
+    option_value
+        .into_iter()
+        .flatten()
+        .map(|name| {
+            parse(name).unwrap_or_else(|| {
+                eprintln!("No such {kind}: {name:?}");
                 process::exit(1)
-            }) {
-                '+' => Some((Operator::LessThan, time - DAY_SECONDS)),
-                '-' => Some((Operator::GreaterThan, time)),
-                _ => Some((Operator::Equal, time - DAY_SECONDS)),
-            }
+            })
+        })
+        .collect()
+}
+
+fn get_time_point(option_value: Option<&String>) -> Option<i64> {
+    option_value.map(|val| {
+        parse_time_point(val).unwrap_or_else(|| {
+            eprintln!("Invalid date or file for time filter: {val:?}, eg: \"2026-09-01 12:00\"");
+            process::exit(1)
+        })
+    })
+}
+
+// A size as with convert_size, a percent of the total (1%) or of the parent directory (5%p)
+fn parse_min_size(input: &str) -> Option<MinSize> {
+    let parse_percent = |percent: &str| {
+        let percent = percent
+            .parse::<f64>()
+            .ok()
+            .filter(|p| (0.0..=100.0).contains(p));
+        if percent.is_none() {
+            eprintln!("Ignoring invalid min-size: {input}");
         }
-        None => None,
+        percent
+    };
+    if let Some(percent) = input.strip_suffix("%p") {
+        parse_percent(percent).map(MinSize::PercentOfParent)
+    } else if let Some(percent) = input.strip_suffix('%') {
+        parse_percent(percent).map(MinSize::PercentOfTotal)
+    } else {
+        convert_min_size(input).map(MinSize::Bytes)
     }
 }
 
 fn convert_min_size(input: &str) -> Option<usize> {
-    let re = Regex::new(r"([0-9]+)(\w*)").unwrap();
+    let size = convert_size(input);
+    if size.is_none() {
+        eprintln!("Ignoring invalid min-size: {input}");
+    }
+    size
+}
+
+// A number with an optional unit, eg: 4096, 10K, 10MiB or 10Mb
+pub fn convert_size(input: &str) -> Option<usize> {
+    let re = Regex::new(r"^([0-9]+)(\w*)$").unwrap();
 
     if let Some(cap) = re.captures(input) {
         let (_, [digits, letters]) = cap.extract();
@@ -253,7 +515,6 @@ fn convert_min_size(input: &str) -> Option<usize> {
                         if letters.is_empty() {
                             Some(parsed_digits)
                         } else {
-                            eprintln!("Ignoring invalid min-size: {input}");
                             None
                         }
                     }
@@ -266,6 +527,16 @@ fn convert_min_size(input: &str) -> Option<usize> {
     }
 }
 
+// MIN..MAX where either side may be left out: 4K..1M, ..4K, 1G..
+fn parse_size_range(input: &str) -> Option<(Option<u64>, Option<u64>)> {
+    let (min, max) = input.split_once("..")?;
+    let bound = |s: &str| match s.trim() {
+        "" => Some(None),
+        s => convert_size(s).map(|size| Some(size as u64)),
+    };
+    Some((bound(min)?, bound(max)?))
+}
+
 fn get_config_locations(base: PathBuf, config_home: Option<PathBuf>) -> Vec<PathBuf> {
     let config_home = config_home.unwrap_or_else(|| base.join(".config"));
     vec![
@@ -356,6 +627,7 @@ mod tests {
         assert_eq!(convert_min_size("55"), Some(55));
         assert_eq!(convert_min_size("12344321"), Some(12344321));
         assert_eq!(convert_min_size("95RUBBISH"), None);
+        assert_eq!(convert_min_size("1.5K"), None);
         assert_eq!(convert_min_size("10Ki"), Some(10 * 1024));
         assert_eq!(convert_min_size("10MiB"), Some(10 * 1024usize.pow(2)));
         assert_eq!(convert_min_size("10M"), Some(10 * 1024usize.pow(2)));
@@ -363,17 +635,115 @@ mod tests {
         assert_eq!(convert_min_size("2Gi"), Some(2 * 1024usize.pow(3)));
     }
 
+    #[test]
+    fn test_get_per_dir_limits() {
+        let c = Config {
+            per_dir: Some(3),
+            min_parent_percent: Some(2.5),
+            ..Default::default()
+        };
+        assert_eq!(c.get_per_dir(&get_args(vec!["dust"])), Some(3));
+        assert_eq!(
+            c.get_per_dir(&get_args(vec!["dust", "--per-dir", "5"])),
+            Some(5)
+        );
+        assert_eq!(
+            c.get_min_size(&get_args(vec!["dust"])),
+            Some(MinSize::PercentOfParent(2.5))
+        );
+        assert_eq!(
+            c.get_min_size(&get_args(vec!["dust", "-z", "1K"])),
+            Some(MinSize::Bytes(1024))
+        );
+        assert_eq!(Config::default().get_per_dir(&get_args(vec!["dust"])), None);
+    }
+
+    #[test]
+    fn test_get_columns() {
+        let c = Config {
+            columns: Some(vec!["disk".into(), "Files".into(), "nope".into()]),
+            ..Default::default()
+        };
+        assert_eq!(
+            c.get_columns(&get_args(vec!["dust"])),
+            vec![Column::Disk, Column::Files]
+        );
+        assert_eq!(
+            c.get_columns(&get_args(vec!["dust", "--columns", "newest,dirs"])),
+            vec![Column::Newest, Column::Dirs]
+        );
+        assert!(
+            Config::default()
+                .get_columns(&get_args(vec!["dust"]))
+                .is_empty()
+        );
+    }
+
+    #[test]
+    fn test_get_size_range() {
+        let c = Config::default();
+        assert_eq!(c.get_size_range(&get_args(vec!["dust"])), None);
+        assert_eq!(
+            c.get_size_range(&get_args(vec!["dust", "--max-size", "4K"])),
+            Some((None, Some(4096)))
+        );
+        assert_eq!(
+            c.get_size_range(&get_args(vec!["dust", "--size-range", "1K..2Ki"])),
+            Some((Some(1024), Some(2048)))
+        );
+        assert_eq!(
+            c.get_size_range(&get_args(vec!["dust", "--size-range", "1M.."])),
+            Some((Some(1024 * 1024), None))
+        );
+
+        // The smaller maximum wins
+        let c = Config {
+            size_range: Some("..8K".to_owned()),
+            ..Default::default()
+        };
+        assert_eq!(
+            c.get_size_range(&get_args(vec!["dust", "--max-size", "4K"])),
+            Some((None, Some(4096)))
+        );
+        assert_eq!(parse_size_range("4K"), None);
+        assert_eq!(parse_size_range("4X.."), None);
+    }
+
     #[test]
     fn test_min_size_from_config_applied_or_overridden() {
         let c = Config {
             min_size: Some("1KiB".to_owned()),
             ..Default::default()
         };
-        assert_eq!(c._get_min_size(None), Some(1024));
-        assert_eq!(c._get_min_size(Some(&"2KiB".into())), Some(2048));
+        assert_eq!(c._get_min_size(None), Some(MinSize::Bytes(1024)));
+        assert_eq!(
+            c._get_min_size(Some(&"2KiB".into())),
+            Some(MinSize::Bytes(2048))
+        );
 
-        assert_eq!(c._get_min_size(Some(&"1kb".into())), Some(1000));
-        assert_eq!(c._get_min_size(Some(&"2KB".into())), Some(2000));
+        assert_eq!(
+            c._get_min_size(Some(&"1kb".into())),
+            Some(MinSize::Bytes(1000))
+        );
+        assert_eq!(
+            c._get_min_size(Some(&"2KB".into())),
+            Some(MinSize::Bytes(2000))
+        );
+    }
+
+    #[test]
+    fn test_min_size_as_percent() {
+        let c = Config {
+            min_size: Some("5%p".to_owned()),
+            ..Default::default()
+        };
+        assert_eq!(c._get_min_size(None), Some(MinSize::PercentOfParent(5.0)));
+        assert_eq!(
+            c._get_min_size(Some(&"0.5%".into())),
+            Some(MinSize::PercentOfTotal(0.5))
+        );
+        assert_eq!(parse_min_size("150%"), None);
+        assert_eq!(parse_min_size("x%p"), None);
     }
 
     #[test]
@@ -442,6 +812,52 @@ mod tests {
         let c = Config::default();
         let args = get_filetime_args(vec!["dust", "--filetime", "changed"]);
         assert_eq!(c.get_filetime(&args), Some(FileTime::Changed));
+
+        // Config is not defined and flag is defined as creation time
+        let c = Config::default();
+        let args = get_filetime_args(vec!["dust", "--filetime", "b"]);
+        assert_eq!(c.get_filetime(&args), Some(FileTime::Created));
+
+        let c = Config::default();
+        let args = get_filetime_args(vec!["dust", "--filetime", "birth"]);
+        assert_eq!(c.get_filetime(&args), Some(FileTime::Created));
+    }
+
+    #[test]
+    fn test_get_fstype() {
+        let c = Config {
+            exclude_fstype: Some(vec!["nfs".to_owned()]),
+            ..Default::default()
+        };
+        let args = get_args(vec!["dust"]);
+        assert_eq!(c.get_exclude_fstype(&args), vec!["nfs"]);
+        assert!(c.get_include_fstype(&args).is_empty());
+
+        let args = get_args(vec!["dust", "--exclude-fstype", "tmpfs,fuse.sshfs"]);
+        assert_eq!(c.get_exclude_fstype(&args), vec!["tmpfs", "fuse.sshfs"]);
+    }
+
+    #[test]
+    fn test_get_gitignore() {
+        let c = Config::default();
+        assert_eq!(c.get_gitignore(&get_args(vec!["dust"])), None);
+        assert_eq!(
+            c.get_gitignore(&get_args(vec!["dust", "--gitignore"])),
+            Some(GitIgnoreMode::Skip)
+        );
+
+        let c = Config {
+            gitignore: Some(true),
+            ..Default::default()
+        };
+        assert_eq!(
+            c.get_gitignore(&get_args(vec!["dust"])),
+            Some(GitIgnoreMode::Skip)
+        );
+        assert_eq!(
+            c.get_gitignore(&get_args(vec!["dust", "--only-gitignored"])),
+            Some(GitIgnoreMode::OnlyIgnored)
+        );
     }
 
     fn get_filetime_args(args: Vec<&str>) -> Cli {
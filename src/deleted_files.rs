@@ -0,0 +1,149 @@
+use std::collections::HashSet;
+
+use crate::dir_walker::WalkData;
+use crate::node::Node;
+
+pub static DELETED_FILES_NAME: &str = "(deleted, still open)";
+
+// Files which have been deleted but are still held open by a process still take
+// up space on the filesystem. They can only be found by looking at what each
+// process has open. Only the devices of the scanned paths are considered.
+#[cfg(target_os = "linux")]
+pub fn get_deleted_open_files(devices: &HashSet<u64>, walk_data: &WalkData) -> Option<Node> {
+    use std::collections::BTreeMap;
+    use std::ffi::{OsStr, OsString};
+    use std::fs;
+    use std::os::unix::ffi::OsStrExt;
+    use std::path::PathBuf;
+
+    use crate::platform::get_metadata;
+
+    // (inode, device) => (path, (disk size, apparent size), [(pid, command)])
+    type DeletedFile = (PathBuf, (u64, u64), Vec<(u32, String)>);
+    let mut deleted_files: BTreeMap<(u64, u64), DeletedFile> = BTreeMap::new();
+
+    for process in fs::read_dir("/proc").ok()?.flatten() {
+        let Ok(pid) = process.file_name().to_string_lossy().parse::<u32>() else {
+            continue;
+        };
+        // Permission is denied for processes of other users unless we are root
+        let Ok(fds) = fs::read_dir(process.path().join("fd")) else {
+            continue;
+        };
+        let mut command = None;
+        for fd in fds.flatten() {
+            let Ok(target) = fs::read_link(fd.path()) else {
+                continue;
+            };
+            let Some(path) = target.as_os_str().as_bytes().strip_suffix(b" (deleted)") else {
+                continue;
+            };
+            // Following the fd link gives the metadata of the deleted file itself
+            let Some((sizes, Some((inode, device)), _)) = get_metadata(fd.path(), true) else {
+                continue;
+            };
+            if !devices.contains(&device) {
+                continue;
+            }
+            let command = command
+                .get_or_insert_with(|| {
+                    fs::read_to_string(process.path().join("comm"))
+                        .map(|c| c.trim_end().to_string())
+                        .unwrap_or_default()
+                })
+                .clone();
+            deleted_files
+                .entry((inode, device))
+                .or_insert_with(|| (PathBuf::from(OsStr::from_bytes(path)), sizes, vec![]))
+                .2
+                .push((pid, command));
+        }
+    }
+
+    if deleted_files.is_empty() {
+        return None;
+    }
+
+    let children: Vec<Node> = deleted_files
+        .into_iter()
+        .map(
+            |(inode_device, (path, (disk_size, apparent_size), holders))| {
+                let holders = holders
+                    .iter()
+                    .map(|(pid, command)| format!("pid {pid} {}", escape_command(command)))
+                    .collect::<Vec<_>>()
+                    .join(", ");
+                let mut name = OsString::from(path);
+                name.push(format!(" [{holders}]"));
+                Node {
+                    name: PathBuf::from(name),
+                    size: get_size(disk_size, apparent_size, walk_data),
+                    disk_size,
+                    apparent_size,
+                    file_count: 1,
+                    dir_count: 0,
+                    newest_mtime: 0,
+                    oldest_mtime: i64::MAX,
+                    newest_btime: None,
+                    children: vec![],
+                    inode_device: Some(inode_device),
+                    depth: 1,
+                }
+            },
+        )
+        .collect();
+
+    Some(Node {
+        name: PathBuf::from(DELETED_FILES_NAME),
+        size: children.iter().map(|c| c.size).sum(),
+        disk_size: children.iter().map(|c| c.disk_size).sum(),
+        apparent_size: children.iter().map(|c| c.apparent_size).sum(),
+        file_count: children.len() as u64,
+        dir_count: 0,
+        newest_mtime: 0,
+        oldest_mtime: i64::MAX,
+        newest_btime: None,
+        children,
+        inode_device: None,
+        depth: 0,
+    })
+}
+
+#[cfg(not(target_os = "linux"))]
+pub fn get_deleted_open_files(_devices: &HashSet<u64>, _walk_data: &WalkData) -> Option<Node> {
+    None
+}
+
+// The holders are written after the file name, a '/' in a command (eg: kworker/0:1)
+// would make the end of it look like a child of the path
+#[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+fn escape_command(command: &str) -> String {
+    command.replace('/', "\u{2215}")
+}
+
+#[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+fn get_size(disk_size: u64, apparent_size: u64, walk_data: &WalkData) -> u64 {
+    if walk_data.by_size_difference {
+        disk_size.abs_diff(apparent_size)
+    } else if walk_data.use_apparent_size {
+        apparent_size
+    } else {
+        disk_size
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::Path;
+
+    #[test]
+    fn test_escape_command_keeps_the_name_whole() {
+        assert_eq!(escape_command("bash"), "bash");
+        let name = format!("/tmp/held.bin [pid 7 {}]", escape_command("kworker/0:1"));
+        assert_eq!(
+            Path::new(&name).file_name().unwrap(),
+            "held.bin [pid 7 kworker\u{2215}0:1]"
+        );
+    }
+}
@@ -4,20 +4,23 @@ use std::io::Error;
 use std::sync::Arc;
 use std::sync::Mutex;
 
+use crate::filter_expr::Candidate;
+use crate::filter_expr::FilterExpr;
+use crate::filter_expr::FilterKind;
+use crate::glob_exclude::ExcludeGlobs;
+use crate::ignore_rules::GitIgnoreMode;
+use crate::ignore_rules::IgnoreRules;
 use crate::node::Node;
 use crate::progress::ORDERING;
 use crate::progress::Operation;
 use crate::progress::PAtomicInfo;
 use crate::progress::RuntimeErrors;
-use crate::utils::is_filtered_out_due_to_file_time;
-use crate::utils::is_filtered_out_due_to_invert_regex;
-use crate::utils::is_filtered_out_due_to_regex;
 use rayon::iter::ParallelBridge;
 use rayon::prelude::ParallelIterator;
-use regex::Regex;
 use std::path::Path;
 use std::path::PathBuf;
 
+use std::collections::HashMap;
 use std::collections::HashSet;
 
 use crate::node::build_node;
@@ -25,6 +28,7 @@ use std::fs::DirEntry;
 
 use crate::node::FileTime;
 use crate::platform::get_metadata;
+use crate::platform::get_ownership;
 
 #[derive(Debug)]
 pub enum Operator {
@@ -33,21 +37,43 @@ pub enum Operator {
     GreaterThan = 2,
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum SkipReason {
+    IgnoredPath,
+    ExcludedGlob,
+    GitIgnored,
+    DustIgnored,
+    Hidden,
+    Filtered(FilterKind),
+}
+
+// What the walk left out, only collected when a report needs it
+#[derive(Default)]
+pub struct SkippedData {
+    // (files, bytes on disk) for each reason content was excluded
+    pub excluded: HashMap<SkipReason, (u64, u64)>,
+    pub other_filesystems: HashSet<PathBuf>,
+    // (files, bytes on disk) of extra links to an inode already seen
+    pub hardlinks: (u64, u64),
+}
+
 pub struct WalkData<'a> {
     pub ignore_directories: HashSet<PathBuf>,
-    pub filter_regex: &'a [Regex],
-    pub invert_filter_regex: &'a [Regex],
+    pub exclude_globs: ExcludeGlobs,
+    pub filters: Vec<(FilterKind, FilterExpr)>,
     pub allowed_filesystems: HashSet<u64>,
-    pub filter_modified_time: Option<(Operator, i64)>,
-    pub filter_accessed_time: Option<(Operator, i64)>,
-    pub filter_changed_time: Option<(Operator, i64)>,
+    pub excluded_mounts: HashSet<PathBuf>,
     pub use_apparent_size: bool,
     pub by_filecount: bool,
     pub by_filetime: &'a Option<FileTime>,
+    pub by_size_difference: bool,
     pub ignore_hidden: bool,
+    pub gitignore: Option<GitIgnoreMode>,
+    pub use_dustignore: bool,
     pub follow_links: bool,
     pub progress_data: Arc<PAtomicInfo>,
     pub errors: Arc<Mutex<RuntimeErrors>>,
+    pub skipped: Option<Arc<Mutex<SkippedData>>>,
 }
 
 pub fn walk_it(dirs: HashSet<PathBuf>, walk_data: &WalkData) -> Vec<Node> {
@@ -57,7 +83,22 @@ pub fn walk_it(dirs: HashSet<PathBuf>, walk_data: &WalkData) -> Vec<Node> {
         .filter_map(|d| {
             let prog_data = &walk_data.progress_data;
             prog_data.clear_state(&d);
-            let node = walk(d, walk_data, 0)?;
+            let ignores = (walk_data.gitignore.is_some() || walk_data.use_dustignore).then(|| {
+                let ignores = Arc::new(IgnoreRules::for_top_level(
+                    &d,
+                    walk_data.gitignore.is_some(),
+                    walk_data.use_dustignore,
+                ));
+                // Everything beneath an ignored top level path is ignored content
+                if walk_data.gitignore == Some(GitIgnoreMode::OnlyIgnored)
+                    && ignores.is_git_ignored(&d, d.is_dir())
+                {
+                    ignores.inside_git_ignored()
+                } else {
+                    ignores
+                }
+            });
+            let node = walk(d.clone(), &d, walk_data, 0, ignores)?;
 
             prog_data.state.store(Operation::PREPARING, ORDERING);
 
@@ -69,11 +110,18 @@ pub fn walk_it(dirs: HashSet<PathBuf>, walk_data: &WalkData) -> Vec<Node> {
 
 // Remove files which have the same inode, we don't want to double count them.
 fn clean_inodes(x: Node, inodes: &mut HashSet<(u64, u64)>, walk_data: &WalkData) -> Option<Node> {
-    if !walk_data.use_apparent_size
+    if (!walk_data.use_apparent_size || walk_data.skipped.is_some())
         && let Some(id) = x.inode_device
         && !inodes.insert(id)
     {
-        return None;
+        if let Some(skipped) = &walk_data.skipped {
+            let mut skipped = skipped.lock().unwrap();
+            skipped.hardlinks.0 += 1;
+            skipped.hardlinks.1 += x.disk_size;
+        }
+        if !walk_data.use_apparent_size {
+            return None;
+        }
     }
 
     // Sort Nodes so iteration order is predictable
@@ -97,9 +145,33 @@ fn clean_inodes(x: Node, inodes: &mut HashSet<(u64, u64)>, walk_data: &WalkData)
         x.size + new_children.iter().map(|c| c.size).sum::<u64>()
     };
 
+    let disk_size = x.disk_size + new_children.iter().map(|c| c.disk_size).sum::<u64>();
+    let apparent_size = x.apparent_size + new_children.iter().map(|c| c.apparent_size).sum::<u64>();
+    let file_count = x.file_count + new_children.iter().map(|c| c.file_count).sum::<u64>();
+    let dir_count = x.dir_count + new_children.iter().map(|c| c.dir_count).sum::<u64>();
+    let newest_mtime = new_children
+        .iter()
+        .map(|c| c.newest_mtime)
+        .fold(x.newest_mtime, i64::max);
+    let oldest_mtime = new_children
+        .iter()
+        .map(|c| c.oldest_mtime)
+        .fold(x.oldest_mtime, i64::min);
+    let newest_btime = new_children
+        .iter()
+        .map(|c| c.newest_btime)
+        .fold(x.newest_btime, Option::max);
+
     Some(Node {
         name: x.name,
         size: actual_size,
+        disk_size,
+        apparent_size,
+        file_count,
+        dir_count,
+        newest_mtime,
+        oldest_mtime,
+        newest_btime,
         children: new_children,
         inode_device: x.inode_device,
         depth: x.depth,
@@ -124,92 +196,205 @@ fn sort_by_inode(a: &Node, b: &Node) -> std::cmp::Ordering {
     }
 }
 
-// Check if `path` is inside ignored directory
+// Check if `path` is an ignored directory. The ignored paths are in the form the
+// walk sees its entries, the parent is checked for top level directories which
+// are inside an ignored path
 fn is_ignored_path(path: &Path, walk_data: &WalkData) -> bool {
-    if walk_data.ignore_directories.contains(path) {
-        return true;
-    }
+    let ignored = &walk_data.ignore_directories;
+    ignored.contains(path) || path.parent().is_some_and(|p| ignored.contains(p))
+}
 
-    // Entry is inside an ignored absolute path
-    // Absolute paths should be canonicalized before being added to `WalkData.ignore_directories`
-    for ignored_path in walk_data.ignore_directories.iter() {
-        if !ignored_path.is_absolute() {
-            continue;
-        }
-        let absolute_entry_path = std::fs::canonicalize(path).unwrap_or_default();
-        if absolute_entry_path.starts_with(ignored_path) {
-            return true;
-        }
+// Adds up the files and disk space of content the walk does not go into. It is
+// part of the walk: the directories are read on its thread pool and the type
+// comes from the directory entry, so each entry is only stat'ed once
+fn get_skipped_size(path: &Path, is_dir: bool) -> (u64, u64) {
+    let own_size = match get_metadata(path, false) {
+        Some(((disk_size, _), _, _)) => disk_size,
+        None => return (0, 0),
+    };
+    if !is_dir {
+        return (1, own_size);
     }
+    let (files, bytes) = fs::read_dir(path)
+        .into_iter()
+        .flatten()
+        .par_bridge()
+        .flatten()
+        .map(|entry| {
+            let is_dir = entry.file_type().is_ok_and(|ft| ft.is_dir());
+            get_skipped_size(&entry.path(), is_dir)
+        })
+        .reduce(|| (0, 0), |(files, bytes), (f, b)| (files + f, bytes + b));
+    (files, bytes + own_size)
+}
 
-    false
+fn record_skipped(entry: &DirEntry, reason: SkipReason, walk_data: &WalkData) {
+    if let Some(skipped) = &walk_data.skipped {
+        let is_dir = entry.file_type().is_ok_and(|ft| ft.is_dir());
+        let (files, bytes) = get_skipped_size(&entry.path(), is_dir);
+        let mut skipped = skipped.lock().unwrap();
+        let total = skipped.excluded.entry(reason).or_default();
+        total.0 += files;
+        total.1 += bytes;
+    }
 }
 
-fn ignore_file(entry: &DirEntry, walk_data: &WalkData) -> bool {
+fn ignore_file(
+    entry: &DirEntry,
+    root: &Path,
+    walk_data: &WalkData,
+    ignores: Option<&IgnoreRules>,
+) -> bool {
     if is_ignored_path(&entry.path(), walk_data) {
+        record_skipped(entry, SkipReason::IgnoredPath, walk_data);
         return true;
     }
 
-    let is_dot_file = entry.file_name().to_str().unwrap_or("").starts_with('.');
-    let follow_links = walk_data.follow_links && entry.file_type().is_ok_and(|ft| ft.is_symlink());
+    let is_dir = entry.file_type().is_ok_and(|ft| ft.is_dir());
+    // Globs only see the path below the scanned directory
+    let path = entry.path();
+    let relative_path = path.strip_prefix(root).unwrap_or(&path);
+    if !walk_data.exclude_globs.is_empty()
+        && walk_data.exclude_globs.is_match(relative_path, is_dir)
+    {
+        record_skipped(entry, SkipReason::ExcludedGlob, walk_data);
+        return true;
+    }
 
-    if !walk_data.allowed_filesystems.is_empty() {
-        let size_inode_device = get_metadata(entry.path(), false, follow_links);
-        if let Some((_size, Some((_id, dev)), _gunk)) = size_inode_device
-            && !walk_data.allowed_filesystems.contains(&dev)
-        {
+    if let Some(ignores) = ignores {
+        if ignores.is_dust_ignored(&entry.path(), is_dir) {
+            record_skipped(entry, SkipReason::DustIgnored, walk_data);
             return true;
         }
-    }
-    if walk_data.filter_accessed_time.is_some()
-        || walk_data.filter_modified_time.is_some()
-        || walk_data.filter_changed_time.is_some()
-    {
-        let size_inode_device = get_metadata(entry.path(), false, follow_links);
-        if let Some((_, _, (modified_time, accessed_time, changed_time))) = size_inode_device
-            && entry.path().is_file()
-            && [
-                (&walk_data.filter_modified_time, modified_time),
-                (&walk_data.filter_accessed_time, accessed_time),
-                (&walk_data.filter_changed_time, changed_time),
-            ]
-            .iter()
-            .any(|(filter_time, actual_time)| {
-                is_filtered_out_due_to_file_time(filter_time, *actual_time)
-            })
-        {
-            return true;
+
+        let is_git_ignored =
+            ignores.in_git_ignored || ignores.is_git_ignored(&entry.path(), is_dir);
+        match walk_data.gitignore {
+            Some(GitIgnoreMode::Skip) if is_git_ignored => {
+                record_skipped(entry, SkipReason::GitIgnored, walk_data);
+                return true;
+            }
+            // Directories which are not ignored may still hold ignored content
+            Some(GitIgnoreMode::OnlyIgnored) if !is_git_ignored && !is_dir => return true,
+            _ => {}
         }
     }
 
-    // Keeping `walk_data.filter_regex.is_empty()` is important for performance reasons, it stops unnecessary work
-    if !walk_data.filter_regex.is_empty()
-        && entry.path().is_file()
-        && is_filtered_out_due_to_regex(walk_data.filter_regex, &entry.path())
-    {
+    let is_dot_file = entry.file_name().to_str().unwrap_or("").starts_with('.');
+    let follow_links = walk_data.follow_links && entry.file_type().is_ok_and(|ft| ft.is_symlink());
+
+    // Mount points of excluded types are known by their path, no stat is needed
+    let is_other_filesystem = if is_dir && walk_data.excluded_mounts.contains(&entry.path()) {
+        true
+    } else if !walk_data.allowed_filesystems.is_empty() {
+        let size_inode_device = get_metadata(entry.path(), follow_links);
+        matches!(size_inode_device, Some((_size, Some((_id, dev)), _gunk))
+            if !walk_data.allowed_filesystems.contains(&dev))
+    } else {
+        false
+    };
+    if is_other_filesystem {
+        if let Some(skipped) = &walk_data.skipped {
+            skipped
+                .lock()
+                .unwrap()
+                .other_filesystems
+                .insert(entry.path());
+        }
+        return true;
+    }
+    // Hidden entries are dropped before the filters stat them
+    if is_dot_file && walk_data.ignore_hidden {
+        record_skipped(entry, SkipReason::Hidden, walk_data);
         return true;
     }
 
-    if !walk_data.invert_filter_regex.is_empty()
-        && entry.path().is_file()
-        && is_filtered_out_due_to_invert_regex(walk_data.invert_filter_regex, &entry.path())
+    // Only files are left out, directories may still hold files which match.
+    // The type of the entry tells them apart, only followed links need a stat
+    let is_file = match entry.file_type() {
+        Ok(ft) if ft.is_symlink() && follow_links => entry.path().is_file(),
+        Ok(ft) => !ft.is_dir(),
+        Err(_) => false,
+    };
+    if !walk_data.filters.is_empty()
+        && is_file
+        && let Some(kind) = get_failed_filter(&entry.path(), root, follow_links, walk_data)
     {
+        record_skipped(entry, SkipReason::Filtered(kind), walk_data);
         return true;
     }
+    false
+}
 
-    is_dot_file && walk_data.ignore_hidden
+// The first filter the file does not match
+fn get_failed_filter(
+    path: &Path,
+    root: &Path,
+    follow_links: bool,
+    walk_data: &WalkData,
+) -> Option<FilterKind> {
+    let filters = &walk_data.filters;
+    // Checking `needs_metadata` is important for performance reasons, it stops unnecessary work
+    let (size, times) = if filters.iter().any(|(_, f)| f.needs_metadata()) {
+        match get_metadata(path, follow_links) {
+            Some(((_, apparent_size), _, times)) if walk_data.use_apparent_size => {
+                (apparent_size, times)
+            }
+            Some(((disk_size, _), _, times)) => (disk_size, times),
+            None => return None,
+        }
+    } else {
+        (0, (0, 0, 0, None))
+    };
+    let ownership = if filters.iter().any(|(_, f)| f.needs_ownership()) {
+        get_ownership(path, follow_links)
+    } else {
+        None
+    };
+    let candidate = Candidate {
+        path,
+        root,
+        size,
+        times,
+        ownership,
+    };
+    filters
+        .iter()
+        .find(|(_, filter)| !filter.is_match(&candidate))
+        .map(|(kind, _)| *kind)
 }
 
-fn walk(dir: PathBuf, walk_data: &WalkData, depth: usize) -> Option<Node> {
+fn walk(
+    dir: PathBuf,
+    root: &Path,
+    walk_data: &WalkData,
+    depth: usize,
+    ignores: Option<Arc<IgnoreRules>>,
+) -> Option<Node> {
     let prog_data = &walk_data.progress_data;
     let errors = &walk_data.errors;
+    let is_ignore_container = walk_data.gitignore == Some(GitIgnoreMode::OnlyIgnored)
+        && ignores.as_ref().is_some_and(|i| !i.in_git_ignored);
 
     let children = if dir.is_dir() {
         let read_dir = fs::read_dir(&dir);
         match read_dir {
             Ok(entries) => {
+                // The listing tells which ignore files the directory holds, so
+                // directories without any are not stat'ed for each of them. It
+                // is only kept when there are ignore files to look for
+                let (entries, dir_ignores): (Box<dyn Iterator<Item = _> + Send>, _) = match &ignores
+                {
+                    Some(ignores) => {
+                        let entries: Vec<_> = entries.collect();
+                        let dir_ignores = ignores.descend(&dir, |name| {
+                            entries.iter().flatten().any(|e| e.file_name() == name)
+                        });
+                        (Box::new(entries.into_iter()), Some(dir_ignores))
+                    }
+                    None => (Box::new(entries), None),
+                };
                 entries
-                    .into_iter()
                     .par_bridge()
                     .filter_map(|entry| {
                         match entry {
@@ -220,17 +405,26 @@ fn walk(dir: PathBuf, walk_data: &WalkData, depth: usize) -> Option<Node> {
 
                                 // return walk(entry.path(), walk_data, depth)
 
-                                if !ignore_file(entry, walk_data)
+                                if !ignore_file(entry, root, walk_data, dir_ignores.as_deref())
                                     && let Ok(data) = entry.file_type()
                                 {
                                     if data.is_dir()
                                         || (walk_data.follow_links && data.is_symlink())
                                     {
-                                        return walk(entry.path(), walk_data, depth + 1);
+                                        let ignores =
+                                            get_child_ignores(entry, walk_data, &dir_ignores);
+                                        return walk(
+                                            entry.path(),
+                                            root,
+                                            walk_data,
+                                            depth + 1,
+                                            ignores,
+                                        );
                                     }
 
                                     let node = build_node(
                                         entry.path(),
+                                        root,
                                         vec![],
                                         data.is_symlink(),
                                         data.is_file(),
@@ -248,7 +442,13 @@ fn walk(dir: PathBuf, walk_data: &WalkData, depth: usize) -> Option<Node> {
                             }
                             Err(ref failed) => {
                                 if handle_error_and_retry(failed, &dir, walk_data) {
-                                    return walk(dir.clone(), walk_data, depth);
+                                    return walk(
+                                        dir.clone(),
+                                        root,
+                                        walk_data,
+                                        depth,
+                                        ignores.clone(),
+                                    );
                                 }
                             }
                         }
@@ -258,7 +458,7 @@ fn walk(dir: PathBuf, walk_data: &WalkData, depth: usize) -> Option<Node> {
             }
             Err(failed) => {
                 if handle_error_and_retry(&failed, &dir, walk_data) {
-                    return walk(dir, walk_data, depth);
+                    return walk(dir, root, walk_data, depth, ignores);
                 } else {
                     vec![]
                 }
@@ -280,7 +480,41 @@ fn walk(dir: PathBuf, walk_data: &WalkData, depth: usize) -> Option<Node> {
     } else {
         false
     };
-    build_node(dir, children, is_symlink, false, depth, walk_data)
+    let node = build_node(dir, root, children, is_symlink, false, depth, walk_data);
+    if is_ignore_container {
+        // Only the ignored content is counted, not the directories holding it
+        node.filter(|n| depth == 0 || !n.children.is_empty())
+            .map(|n| Node {
+                size: 0,
+                disk_size: 0,
+                apparent_size: 0,
+                dir_count: 0,
+                newest_mtime: 0,
+                oldest_mtime: i64::MAX,
+                newest_btime: None,
+                ..n
+            })
+    } else {
+        node
+    }
+}
+
+fn get_child_ignores(
+    entry: &DirEntry,
+    walk_data: &WalkData,
+    ignores: &Option<Arc<IgnoreRules>>,
+) -> Option<Arc<IgnoreRules>> {
+    match ignores {
+        // In the ignored only mode all the content of an ignored directory is kept
+        Some(i)
+            if walk_data.gitignore == Some(GitIgnoreMode::OnlyIgnored)
+                && !i.in_git_ignored
+                && i.is_git_ignored(&entry.path(), true) =>
+        {
+            Some(i.inside_git_ignored())
+        }
+        _ => ignores.clone(),
+    }
 }
 
 fn handle_error_and_retry(failed: &Error, dir: &Path, walk_data: &WalkData) -> bool {
@@ -330,6 +564,13 @@ mod tests {
         Node {
             name: PathBuf::new(),
             size: 10,
+            disk_size: 10,
+            apparent_size: 10,
+            file_count: 1,
+            dir_count: 0,
+            newest_mtime: 0,
+            oldest_mtime: i64::MAX,
+            newest_btime: None,
             children: vec![],
             inode_device: Some((5, 6)),
             depth: 0,
@@ -342,19 +583,21 @@ mod tests {
         let indicator = PIndicator::build_me();
         WalkData {
             ignore_directories: HashSet::new(),
-            filter_regex: &[],
-            invert_filter_regex: &[],
+            exclude_globs: ExcludeGlobs::default(),
+            filters: vec![],
             allowed_filesystems: HashSet::new(),
-            filter_modified_time: Some((Operator::GreaterThan, 0)),
-            filter_accessed_time: Some((Operator::GreaterThan, 0)),
-            filter_changed_time: Some((Operator::GreaterThan, 0)),
+            excluded_mounts: HashSet::new(),
             use_apparent_size,
             by_filecount: false,
             by_filetime: &None,
+            by_size_difference: false,
             ignore_hidden: false,
+            gitignore: None,
+            use_dustignore: false,
             follow_links: false,
             progress_data: indicator.data.clone(),
             errors: Arc::new(Mutex::new(RuntimeErrors::default())),
+            skipped: None,
         }
     }
 
@@ -375,6 +618,24 @@ mod tests {
         assert_eq!(clean_inodes(n.clone(), &mut inodes, &walkdata), None);
     }
 
+    #[test]
+    #[allow(clippy::redundant_clone)]
+    fn test_ignored_hardlinks_are_recorded() {
+        let mut inodes = HashSet::new();
+        let n = create_node();
+        let mut walkdata = create_walker(true);
+        walkdata.skipped = Some(Arc::new(Mutex::new(SkippedData::default())));
+
+        // Using apparent size the duplicate is kept but still recorded
+        clean_inodes(n.clone(), &mut inodes, &walkdata);
+        assert_eq!(
+            clean_inodes(n.clone(), &mut inodes, &walkdata),
+            Some(n.clone())
+        );
+        let skipped = walkdata.skipped.unwrap();
+        assert_eq!(skipped.lock().unwrap().hardlinks, (1, 10));
+    }
+
     #[test]
     #[allow(clippy::redundant_clone)]
     fn test_should_not_ignore_files_if_using_apparent_size() {
@@ -400,6 +661,13 @@ mod tests {
         let a = Node {
             name: PathBuf::from_str("a").unwrap(),
             size: 0,
+            disk_size: 0,
+            apparent_size: 0,
+            file_count: 0,
+            dir_count: 0,
+            newest_mtime: 0,
+            oldest_mtime: i64::MAX,
+            newest_btime: None,
             children: vec![],
             inode_device: Some((3, 66310)),
             depth: 0,
@@ -408,6 +676,13 @@ mod tests {
         let b = Node {
             name: PathBuf::from_str("b").unwrap(),
             size: 0,
+            disk_size: 0,
+            apparent_size: 0,
+            file_count: 0,
+            dir_count: 0,
+            newest_mtime: 0,
+            oldest_mtime: i64::MAX,
+            newest_btime: None,
             children: vec![],
             inode_device: None,
             depth: 0,
@@ -416,6 +691,13 @@ mod tests {
         let c = Node {
             name: PathBuf::from_str("c").unwrap(),
             size: 0,
+            disk_size: 0,
+            apparent_size: 0,
+            file_count: 0,
+            dir_count: 0,
+            newest_mtime: 0,
+            oldest_mtime: i64::MAX,
+            newest_btime: None,
             children: vec![],
             inode_device: Some((1, 66310)),
             depth: 0,
@@ -1,8 +1,13 @@
+use crate::cli::Column;
+use crate::cli::Heatmap;
 use crate::display_node::DisplayNode;
+use crate::filesystem::FilesystemInfo;
+use crate::filesystem::find_filesystem;
 use crate::node::FileTime;
+use crate::theme::Theme;
 
 use lscolors::{LsColors, Style};
-use nu_ansi_term::Color::{DarkGray, Red};
+use nu_ansi_term::Color;
 
 use unicode_width::{UnicodeWidthChar, UnicodeWidthStr};
 
@@ -11,34 +16,50 @@ use stfu8::encode_u8;
 use chrono::{DateTime, Local, TimeZone, Utc};
 use std::cmp::max;
 use std::cmp::min;
+use std::collections::HashMap;
 use std::fs;
 use std::iter::repeat_n;
 use std::path::Path;
+use std::path::PathBuf;
 use thousands::Separable;
 
 pub static SI_UNITS: [&str; 5] = ["P", "T", "G", "M", "K"];
 pub static IEC_UNITS: [&str; 5] = ["Pi", "Ti", "Gi", "Mi", "Ki"];
-static BLOCKS: [char; 5] = ['█', '▓', '▒', '░', ' '];
 const FILETIME_SHOW_LENGTH: usize = 19;
+const RATIO_SHOW_LENGTH: usize = 6;
+const FILESYSTEM_PERCENT_SHOW_LENGTH: usize = 7;
 
 pub struct InitialDisplayData {
     pub short_paths: bool,
     pub is_reversed: bool,
     pub colors_on: bool,
     pub dim: bool,
+    pub heatmap: Option<Heatmap>,
     pub by_filecount: bool,
     pub by_filetime: Option<FileTime>,
+    pub compare_sizes: bool,
+    pub columns: Vec<Column>,
     pub is_screen_reader: bool,
     pub output_format: String,
     pub bars_on_right: bool,
+    pub filesystem_percent: bool,
+    pub percent_of_parent: bool,
+    pub filesystems: Vec<FilesystemInfo>,
+    pub mount_point_types: HashMap<PathBuf, String>,
+    pub theme: Theme,
 }
 
 pub struct DisplayData {
     pub initial: InitialDisplayData,
     pub num_chars_needed_on_left_most: usize,
+    pub column_widths: Vec<usize>,
     pub base_size: u64,
     pub longest_string_length: usize,
     pub ls_colors: LsColors,
+    // For the age of the heatmap
+    pub now: i64,
+    // The name of the root when its row is drawn, for the total style
+    pub total_name: Option<PathBuf>,
 }
 
 impl DisplayData {
@@ -71,15 +92,64 @@ impl DisplayData {
         }
     }
 
-    fn percent_size(&self, node: &DisplayNode) -> f32 {
-        let result = node.size as f32 / self.base_size as f32;
+    fn percent_size(&self, node: &DisplayNode, parent_size: u64) -> f32 {
+        let base_size = if self.initial.percent_of_parent {
+            parent_size
+        } else {
+            self.base_size
+        };
+        let result = node.size as f32 / base_size as f32;
         if result.is_normal() { result } else { 0.0 }
     }
+
+    fn percent_of_filesystem(&self, node: &DisplayNode) -> Option<f32> {
+        let filesystem = find_filesystem(&self.initial.filesystems, &node.name)?;
+        let result = node.size as f32 / filesystem.total as f32;
+        Some(if result.is_normal() { result } else { 0.0 })
+    }
+
+    fn format_percents(&self, node: &DisplayNode, parent_size: u64) -> String {
+        let percent = self.percent_size(node, parent_size) * 100.0;
+        let percent_size_str = format!("{percent:.0}%");
+        if self.initial.filesystem_percent {
+            let filesystem_percent_str = self
+                .percent_of_filesystem(node)
+                .map(|p| format!("{:.1}%", p * 100.0))
+                .unwrap_or_default();
+            let width = FILESYSTEM_PERCENT_SHOW_LENGTH - 1;
+            format!("{percent_size_str:>4} {filesystem_percent_str:>width$}")
+        } else {
+            format!("{percent_size_str:>4}")
+        }
+    }
+
+    fn get_heatmap_color(&self, node: &DisplayNode) -> Option<Color> {
+        const DAY: i64 = 24 * 60 * 60;
+        // Nothing below had a modification time
+        if node.newest_mtime == 0 {
+            return None;
+        }
+        let age = self.now - node.newest_mtime;
+        let index = if age < 7 * DAY {
+            0
+        } else if age < 30 * DAY {
+            1
+        } else if age < 180 * DAY {
+            2
+        } else if age < 2 * 365 * DAY {
+            3
+        } else {
+            4
+        };
+        Some(self.initial.theme.heatmap[index])
+    }
 }
 
 struct DrawData<'a> {
     indent: String,
     percent_bar: String,
+    // The size of the node drawn above, for --percent-of-parent
+    parent_size: u64,
     display_data: &'a DisplayData,
 }
 
@@ -95,9 +165,10 @@ impl DrawData<'_> {
             return level.to_string();
         }
         let chars_in_bar = self.percent_bar.chars().count();
-        let num_bars = chars_in_bar as f32 * self.display_data.percent_size(node);
+        let num_bars = chars_in_bar as f32 * self.display_data.percent_size(node, self.parent_size);
         let mut num_not_my_bar = (chars_in_bar as i32) - num_bars as i32;
 
+        let blocks = &self.display_data.initial.theme.bar_chars;
         let mut new_bar = "".to_string();
         let idx = 5 - level.clamp(1, 4);
 
@@ -110,9 +181,9 @@ impl DrawData<'_> {
         for c in itr {
             num_not_my_bar -= 1;
             if num_not_my_bar <= 0 {
-                new_bar.push(BLOCKS[0]);
-            } else if c == BLOCKS[0] {
-                new_bar.push(BLOCKS[idx]);
+                new_bar.push(blocks[0]);
+            } else if c == blocks[0] {
+                new_bar.push(blocks[idx]);
             } else {
                 new_bar.push(c);
             }
@@ -132,13 +203,92 @@ pub fn draw_it(
     terminal_width: usize,
     skip_total: bool,
 ) {
-    let num_chars_needed_on_left_most = if idd.by_filecount {
+    let num_indent_chars = 3;
+    let (mut display_data, first_size_bar) = get_display_data(
+        idd,
+        root_node,
+        no_percent_bars,
+        terminal_width,
+        num_indent_chars,
+    );
+    if !skip_total {
+        display_data.total_name = Some(root_node.name.clone());
+    }
+    let draw_data = DrawData {
+        indent: "".to_string(),
+        percent_bar: first_size_bar,
+        parent_size: root_node.size,
+        display_data: &display_data,
+    };
+
+    if !skip_total {
+        display_node(root_node, &draw_data, true, true);
+    } else {
+        for (count, c) in root_node
+            .get_children_from_node(draw_data.display_data.initial.is_reversed)
+            .enumerate()
+        {
+            let is_biggest = display_data.is_biggest(count, root_node.num_siblings());
+            let was_i_last = display_data.is_last(count, root_node.num_siblings());
+            display_node(c, &draw_data, is_biggest, was_i_last);
+        }
+    }
+}
+
+// The children of the root one per line, in their order and without tree drawing
+pub fn draw_flat(
+    idd: InitialDisplayData,
+    root_node: &DisplayNode,
+    no_percent_bars: bool,
+    terminal_width: usize,
+) {
+    let (display_data, first_size_bar) =
+        get_display_data(idd, root_node, no_percent_bars, terminal_width, 0);
+    let draw_data = DrawData {
+        indent: "".to_string(),
+        percent_bar: first_size_bar,
+        parent_size: root_node.size,
+        display_data: &display_data,
+    };
+    let biggest = root_node.children.iter().max_by_key(|c| c.size);
+
+    for c in root_node.get_children_from_node(display_data.initial.is_reversed) {
+        let bar_text = draw_data.generate_bar(c, 1);
+        let is_biggest = biggest.is_some_and(|b| std::ptr::eq(b, c));
+        println!(
+            "{}",
+            format_string(c, root_node.size, "", &bar_text, is_biggest, &display_data)
+        );
+    }
+}
+
+fn get_display_data(
+    idd: InitialDisplayData,
+    root_node: &DisplayNode,
+    no_percent_bars: bool,
+    terminal_width: usize,
+    num_indent_chars: usize,
+) -> (DisplayData, String) {
+    let column_widths: Vec<_> = idd
+        .columns
+        .iter()
+        .map(|column| get_column_width(root_node, *column, &idd.output_format))
+        .collect();
+    let num_chars_needed_on_left_most = if !column_widths.is_empty() {
+        // separated by spaces
+        column_widths.iter().sum::<usize>() + column_widths.len() - 1
+    } else if idd.by_filecount {
         let max_size = root_node.size;
         max_size.separate_with_commas().chars().count()
     } else if idd.by_filetime.is_some() {
         FILETIME_SHOW_LENGTH
+    } else if idd.compare_sizes {
+        // disk size, apparent size and ratio columns separated by spaces
+        let disk = find_biggest_size_str(root_node, &idd.output_format, |n| n.disk_size);
+        let apparent = find_biggest_size_str(root_node, &idd.output_format, |n| n.apparent_size);
+        max(disk, apparent) * 2 + RATIO_SHOW_LENGTH + 2
     } else {
-        find_biggest_size_str(root_node, &idd.output_format)
+        find_biggest_size_str(root_node, &idd.output_format, |n| n.size)
     };
 
     assert!(
@@ -147,62 +297,103 @@ pub fn draw_it(
     );
 
     let allowed_width = terminal_width - num_chars_needed_on_left_most - 2;
-    let num_indent_chars = 3;
     let longest_string_length =
         find_longest_dir_name(root_node, num_indent_chars, allowed_width, &idd);
 
-    let max_bar_length = if no_percent_bars || longest_string_length + 7 >= allowed_width {
-        0
+    let percents_length = if idd.filesystem_percent {
+        7 + FILESYSTEM_PERCENT_SHOW_LENGTH
     } else {
-        allowed_width - longest_string_length - 7
+        7
     };
+    let max_bar_length =
+        if no_percent_bars || longest_string_length + percents_length >= allowed_width {
+            0
+        } else {
+            allowed_width - longest_string_length - percents_length
+        };
 
-    let first_size_bar = repeat_n(BLOCKS[0], max_bar_length).collect();
+    let first_size_bar: String = repeat_n(idd.theme.bar_chars[0], max_bar_length).collect();
 
     let display_data = DisplayData {
         initial: idd,
         num_chars_needed_on_left_most,
+        column_widths,
         base_size: root_node.size,
         longest_string_length,
         ls_colors: LsColors::from_env().unwrap_or_default(),
+        now: Local::now().timestamp(),
+        total_name: None,
     };
-    let draw_data = DrawData {
-        indent: "".to_string(),
-        percent_bar: first_size_bar,
-        display_data: &display_data,
-    };
+    (display_data, first_size_bar)
+}
 
-    if !skip_total {
-        display_node(root_node, &draw_data, true, true);
+pub fn draw_filesystem_info(filesystems: &[FilesystemInfo], output_format: &str) {
+    for filesystem in filesystems {
+        println!("{}", format_filesystem_info(filesystem, output_format));
+    }
+}
+
+fn format_filesystem_info(filesystem: &FilesystemInfo, output_format: &str) -> String {
+    let root = filesystem.root.display();
+    let mount_point = filesystem
+        .mount_point
+        .as_ref()
+        .map(|m| m.display().to_string())
+        .unwrap_or_else(|| "?".to_string());
+    let fs_type = filesystem.fs_type.as_deref().unwrap_or("unknown");
+    let used = human_readable_number(filesystem.used, output_format);
+    let total = human_readable_number(filesystem.total, output_format);
+    let free = human_readable_number(filesystem.free, output_format);
+    let used_percent = get_percent_str(filesystem.used, filesystem.total);
+    let inodes_used = filesystem.inodes_used.separate_with_commas();
+    let inodes_total = filesystem.inodes_total.separate_with_commas();
+    let inodes_percent = get_percent_str(filesystem.inodes_used, filesystem.inodes_total);
+    format!(
+        "{root} on {mount_point} ({fs_type}): {used} used of {total} ({used_percent}), {free} free, \
+         {inodes_used} of {inodes_total} inodes used ({inodes_percent})"
+    )
+}
+
+fn get_percent_str(part: u64, whole: u64) -> String {
+    if whole == 0 {
+        "-".to_string()
     } else {
-        for (count, c) in root_node
-            .get_children_from_node(draw_data.display_data.initial.is_reversed)
-            .enumerate()
-        {
-            let is_biggest = display_data.is_biggest(count, root_node.num_siblings());
-            let was_i_last = display_data.is_last(count, root_node.num_siblings());
-            display_node(c, &draw_data, is_biggest, was_i_last);
-        }
+        format!("{:.0}%", part as f64 * 100.0 / whole as f64)
     }
 }
 
-fn find_biggest_size_str(node: &DisplayNode, output_format: &str) -> usize {
-    let mut mx = human_readable_number(node.size, output_format)
+fn find_biggest_size_str(
+    node: &DisplayNode,
+    output_format: &str,
+    size_of: fn(&DisplayNode) -> u64,
+) -> usize {
+    let mut mx = human_readable_number(size_of(node), output_format)
         .chars()
         .count();
     for n in node.children.iter() {
-        mx = max(mx, find_biggest_size_str(n, output_format));
+        mx = max(mx, find_biggest_size_str(n, output_format, size_of));
     }
     mx
 }
 
+fn get_column_width(root_node: &DisplayNode, column: Column, output_format: &str) -> usize {
+    match column {
+        Column::Disk => find_biggest_size_str(root_node, output_format, |n| n.disk_size),
+        Column::Apparent => find_biggest_size_str(root_node, output_format, |n| n.apparent_size),
+        // Nothing below has more than the root
+        Column::Files => root_node.file_count.separate_with_commas().chars().count(),
+        Column::Dirs => root_node.dir_count.separate_with_commas().chars().count(),
+        Column::Newest | Column::Oldest => FILETIME_SHOW_LENGTH,
+    }
+}
+
 fn find_longest_dir_name(
     node: &DisplayNode,
     indent: usize,
     terminal: usize,
     idd: &InitialDisplayData,
 ) -> usize {
-    let printable_name = get_printable_name(&node.name, idd.short_paths);
+    let printable_name = get_display_name(node, idd);
 
     let longest = if idd.is_screen_reader {
         UnicodeWidthStr::width(&*printable_name) + 1
@@ -226,15 +417,29 @@ fn display_node(node: &DisplayNode, draw_data: &DrawData, is_biggest: bool, is_l
     let level = ((indent.chars().count() - 1) / 2) - 1;
     let bar_text = draw_data.generate_bar(node, level);
 
-    let to_print = format_string(node, &indent, &bar_text, is_biggest, draw_data.display_data);
+    let to_print = format_string(
+        node,
+        draw_data.parent_size,
+        &indent,
+        &bar_text,
+        is_biggest,
+        draw_data.display_data,
+    );
 
     if !draw_data.display_data.initial.is_reversed {
         println!("{to_print}")
     }
 
+    // Against the parent every row has the whole width, shaded for its depth
+    let percent_bar = if draw_data.display_data.initial.percent_of_parent {
+        draw_data.percent_bar.clone()
+    } else {
+        bar_text
+    };
     let dd = DrawData {
         indent: clean_indentation_string(&indent),
-        percent_bar: bar_text,
+        percent_bar,
+        parent_size: node.size,
         display_data: draw_data.display_data,
     };
 
@@ -289,8 +494,17 @@ pub fn get_printable_name<P: AsRef<Path>>(dir_name: &P, short_paths: bool) -> St
     encode_u8(printable_name.display().to_string().as_bytes())
 }
 
+// Mount points crossed by the walk are followed by their filesystem type
+fn get_display_name(node: &DisplayNode, idd: &InitialDisplayData) -> String {
+    let name = get_printable_name(&node.name, idd.short_paths);
+    match idd.mount_point_types.get(&node.name) {
+        Some(fs_type) => format!("{name} [{fs_type}]"),
+        None => name,
+    }
+}
+
 fn pad_or_trim_filename(node: &DisplayNode, indent: &str, display_data: &DisplayData) -> String {
-    let name = get_printable_name(&node.name, display_data.initial.short_paths);
+    let name = get_display_name(node, &display_data.initial);
     let indent_and_name = format!("{indent} {name}");
     let width = UnicodeWidthStr::width(&*indent_and_name);
 
@@ -334,12 +548,15 @@ fn maybe_trim_filename(name_in: String, indent: &str, display_data: &DisplayData
 
 pub fn format_string(
     node: &DisplayNode,
+    parent_size: u64,
     indent: &str,
     bars: &str,
     is_biggest: bool,
     display_data: &DisplayData,
 ) -> String {
-    let (percent, name_and_padding) = get_name_percent(node, indent, bars, display_data);
+    let indent = &display_data.initial.theme.get_tree_str(indent);
+    let (percent, name_and_padding) =
+        get_name_percent(node, parent_size, indent, bars, display_data);
     let pretty_size = get_pretty_size(node, is_biggest, display_data);
     let pretty_name = get_pretty_name(node, name_and_padding, display_data);
     // we can clean this and the method below somehow, not sure yet
@@ -355,53 +572,139 @@ pub fn format_string(
 
 fn get_name_percent(
     node: &DisplayNode,
+    parent_size: u64,
     indent: &str,
     bar_chart: &str,
     display_data: &DisplayData,
 ) -> (String, String) {
     if display_data.initial.is_screen_reader {
-        let percent = display_data.percent_size(node) * 100.0;
-        let percent_size_str = format!("{percent:.0}%");
-        let percents = format!(" {percent_size_str:>4}",);
+        let percents = format!(" {}", display_data.format_percents(node, parent_size));
         let name = pad_or_trim_filename(node, "", display_data);
         (percents, name)
     // Bar chart being empty may come from either config or the screen not being wide enough
     } else if !bar_chart.is_empty() {
-        let percent = display_data.percent_size(node) * 100.0;
-        let percent_size_str = format!("{percent:.0}%");
-        let colored_bar = if display_data.initial.dim {
-            format!("{}", DarkGray.paint(bar_chart))
+        let theme = &display_data.initial.theme;
+        let bar_color = match display_data.initial.heatmap {
+            _ if !display_data.initial.colors_on => None,
+            Some(Heatmap::Bar) => display_data.get_heatmap_color(node),
+            _ => theme.get_gradient_color(display_data.percent_size(node, parent_size)),
+        };
+        let colored_bar = if let Some(color) = bar_color {
+            format!("{}", color.paint(bar_chart))
+        } else if display_data.initial.dim {
+            format!("{}", theme.dim.paint(bar_chart))
         } else {
             bar_chart.to_string()
         };
-        let percents = format!("│{colored_bar} │ {percent_size_str:>4}");
+        let line = theme.get_vertical_line();
+        let percents = format!(
+            "{line}{colored_bar} {line} {}",
+            display_data.format_percents(node, parent_size)
+        );
         let name_and_padding = pad_or_trim_filename(node, indent, display_data);
         (percents, name_and_padding)
     } else {
-        let n = get_printable_name(&node.name, display_data.initial.short_paths);
+        let n = get_display_name(node, &display_data.initial);
         let name = maybe_trim_filename(n, indent, display_data);
         ("".into(), name)
     }
 }
 
 fn get_pretty_size(node: &DisplayNode, is_biggest: bool, display_data: &DisplayData) -> String {
-    let output = if display_data.initial.by_filecount {
+    let output = if !display_data.initial.columns.is_empty() {
+        get_pretty_columns(node, display_data)
+    } else if display_data.initial.by_filecount {
         node.size.separate_with_commas()
+    } else if display_data.initial.by_filetime == Some(FileTime::Created)
+        && node.newest_btime.is_none()
+    {
+        // No birth time was recorded for anything below this node
+        format!("{:^FILETIME_SHOW_LENGTH$}", "unknown")
     } else if display_data.initial.by_filetime.is_some() {
         get_pretty_file_modified_time(node.size as i64)
+    } else if display_data.initial.compare_sizes {
+        get_pretty_compare_sizes(node, display_data)
     } else {
         human_readable_number(node.size, &display_data.initial.output_format)
     };
-    let spaces_to_add = display_data.num_chars_needed_on_left_most - output.chars().count();
+    let spaces_to_add = display_data
+        .num_chars_needed_on_left_most
+        .saturating_sub(output.chars().count());
     let output = " ".repeat(spaces_to_add) + output.as_str();
 
-    if is_biggest && display_data.initial.colors_on {
-        format!("{}", Red.paint(output))
+    if let Some(style) = get_total_style(node, display_data) {
+        format!("{}", style.paint(output))
+    } else if is_biggest && display_data.initial.colors_on {
+        format!("{}", display_data.initial.theme.biggest.paint(output))
     } else {
         output
     }
 }
 
+fn get_pretty_columns(node: &DisplayNode, display_data: &DisplayData) -> String {
+    let output_format = &display_data.initial.output_format;
+    display_data
+        .initial
+        .columns
+        .iter()
+        .zip(display_data.column_widths.iter())
+        .map(|(column, width)| {
+            let value = match column {
+                Column::Disk => human_readable_number(node.disk_size, output_format),
+                Column::Apparent => human_readable_number(node.apparent_size, output_format),
+                Column::Files => node.file_count.separate_with_commas(),
+                Column::Dirs => node.dir_count.separate_with_commas(),
+                Column::Newest => get_pretty_mtime(node.newest_mtime),
+                Column::Oldest => get_pretty_mtime(node.oldest_mtime),
+            };
+            format!("{value:>width$}")
+        })
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+// 0 and i64::MAX stand for no files below
+fn get_pretty_mtime(timestamp: i64) -> String {
+    if timestamp == 0 || timestamp == i64::MAX {
+        "-".into()
+    } else {
+        get_pretty_file_modified_time(timestamp)
+    }
+}
+
+fn get_pretty_compare_sizes(node: &DisplayNode, display_data: &DisplayData) -> String {
+    let output_format = &display_data.initial.output_format;
+    let width = (display_data.num_chars_needed_on_left_most - RATIO_SHOW_LENGTH - 2) / 2;
+    let disk = human_readable_number(node.disk_size, output_format);
+    let apparent = human_readable_number(node.apparent_size, output_format);
+    let ratio = get_size_ratio(node.disk_size, node.apparent_size);
+    format!("{disk:>width$} {apparent:>width$} {ratio:>RATIO_SHOW_LENGTH$}")
+}
+
+// Apparent size over disk size: above 1 means a sparse or compressed file
+fn get_size_ratio(disk_size: u64, apparent_size: u64) -> String {
+    if disk_size == 0 {
+        return "-".into();
+    }
+    let ratio = apparent_size as f64 / disk_size as f64;
+    if ratio >= 1000.0 {
+        ">999x".into()
+    } else if ratio >= 10.0 {
+        format!("{ratio:.0}x")
+    } else {
+        format!("{ratio:.2}x")
+    }
+}
+
+// A size as shown in a line, or the time with filetime
+pub fn get_size_str(size: u64, by_filetime: bool, output_format: &str) -> String {
+    if by_filetime {
+        get_pretty_file_modified_time(size as i64)
+    } else {
+        human_readable_number(size, output_format)
+    }
+}
+
 fn get_pretty_file_modified_time(timestamp: i64) -> String {
     let datetime: DateTime<Utc> = Utc.timestamp_opt(timestamp, 0).unwrap();
 
@@ -415,7 +718,17 @@ fn get_pretty_name(
     name_and_padding: String,
     display_data: &DisplayData,
 ) -> String {
-    if display_data.initial.colors_on {
+    let heatmap_color = match display_data.initial.heatmap {
+        Some(Heatmap::Name) if display_data.initial.colors_on => {
+            display_data.get_heatmap_color(node)
+        }
+        _ => None,
+    };
+    if let Some(style) = get_total_style(node, display_data) {
+        format!("{}", style.paint(name_and_padding))
+    } else if let Some(color) = heatmap_color {
+        format!("{}", color.paint(name_and_padding))
+    } else if display_data.initial.colors_on {
         let meta_result = fs::metadata(&node.name);
         let directory_color = display_data
             .ls_colors
@@ -430,6 +743,15 @@ fn get_pretty_name(
     }
 }
 
+fn get_total_style(node: &DisplayNode, display_data: &DisplayData) -> Option<nu_ansi_term::Style> {
+    if !display_data.initial.colors_on {
+        return None;
+    }
+    let total_name = display_data.total_name.as_ref()?;
+    let style = display_data.initial.theme.total?;
+    (*total_name == node.name).then_some(style)
+}
+
 pub fn get_units(output_str: &str) -> &'static [&'static str; 5] {
     if get_type_of_thousand(output_str) == 1024 {
         &IEC_UNITS
@@ -505,18 +827,29 @@ mod tests {
             is_reversed: false,
             colors_on: false,
             dim: false,
+            heatmap: None,
             by_filecount: false,
             by_filetime: None,
+            compare_sizes: false,
+            columns: vec![],
             is_screen_reader: false,
             output_format: "".into(),
             bars_on_right: false,
+            filesystem_percent: false,
+            percent_of_parent: false,
+            filesystems: vec![],
+            mount_point_types: HashMap::new(),
+            theme: Theme::default(),
         };
         DisplayData {
             initial,
             num_chars_needed_on_left_most: 5,
+            column_widths: vec![],
             base_size: 2_u64.pow(12), // 4.0K
             longest_string_length,
             ls_colors: LsColors::from_env().unwrap_or_default(),
+            now: 0,
+            total_name: None,
         }
     }
 
@@ -526,13 +859,20 @@ mod tests {
             name: PathBuf::from("/short"),
             size: 2_u64.pow(12), // This is 4.0K
             children: vec![],
+            disk_size: 2_u64.pow(12),
+            apparent_size: 2_u64.pow(12),
+            file_count: 1,
+            dir_count: 0,
+            newest_mtime: 0,
+            oldest_mtime: i64::MAX,
+            newest_btime: None,
         };
         let indent = "┌─┴";
         let percent_bar = "";
         let is_biggest = false;
         let data = get_fake_display_data(20);
 
-        let s = format_string(&n, indent, percent_bar, is_biggest, &data);
+        let s = format_string(&n, data.base_size, indent, percent_bar, is_biggest, &data);
         assert_eq!(s, "4.0Ki ┌─┴ short");
     }
 
@@ -543,13 +883,20 @@ mod tests {
             name: PathBuf::from(name),
             size: 2_u64.pow(12), // This is 4.0K
             children: vec![],
+            disk_size: 2_u64.pow(12),
+            apparent_size: 2_u64.pow(12),
+            file_count: 1,
+            dir_count: 0,
+            newest_mtime: 0,
+            oldest_mtime: i64::MAX,
+            newest_btime: None,
         };
         let indent = "┌─┴";
         let percent_bar = "";
         let is_biggest = false;
 
         let data = get_fake_display_data(64);
-        let s = format_string(&n, indent, percent_bar, is_biggest, &data);
+        let s = format_string(&n, data.base_size, indent, percent_bar, is_biggest, &data);
         assert_eq!(
             s,
             "4.0Ki ┌─┴ very_long_name_longer_than_the_eighty_character_limit_very_.."
@@ -565,6 +912,13 @@ mod tests {
             name: PathBuf::from(name),
             size: 2_u64.pow(12),
             children: vec![],
+            disk_size: 2_u64.pow(12),
+            apparent_size: 2_u64.pow(12),
+            file_count: 1,
+            dir_count: 0,
+            newest_mtime: 0,
+            oldest_mtime: i64::MAX,
+            newest_btime: None,
         };
         let indent = "┌─┴";
         let percent_bar = "";
@@ -573,7 +927,7 @@ mod tests {
         // longest_string_length of 20 leaves 20 - 3 = 17 columns for the name,
         // of which 2 are the '..' marker: 7 wide chars (14 cols) then '..'
         let data = get_fake_display_data(20);
-        let s = format_string(&n, indent, percent_bar, is_biggest, &data);
+        let s = format_string(&n, data.base_size, indent, percent_bar, is_biggest, &data);
         assert_eq!(s, "4.0Ki ┌─┴ ラウトは難しい..");
         assert_eq!(UnicodeWidthStr::width(&*s), 26);
     }
@@ -584,6 +938,13 @@ mod tests {
             name: PathBuf::from("/short"),
             size: 2_u64.pow(12), // This is 4.0K
             children: vec![],
+            disk_size: 2_u64.pow(12),
+            apparent_size: 2_u64.pow(12),
+            file_count: 1,
+            dir_count: 0,
+            newest_mtime: 0,
+            oldest_mtime: i64::MAX,
+            newest_btime: None,
         };
         let indent = "";
         let percent_bar = "3";
@@ -591,10 +952,161 @@ mod tests {
         let mut data = get_fake_display_data(20);
         data.initial.is_screen_reader = true;
 
-        let s = format_string(&n, indent, percent_bar, is_biggest, &data);
+        let s = format_string(&n, data.base_size, indent, percent_bar, is_biggest, &data);
         assert_eq!(s, "short               3 4.0Ki 100%");
     }
 
+    #[test]
+    fn test_format_str_compare_sizes() {
+        let n = DisplayNode {
+            name: PathBuf::from("/sparse.img"),
+            size: 2_u64.pow(12),
+            children: vec![],
+            disk_size: 2_u64.pow(12),         // 4.0K
+            apparent_size: 2_u64.pow(20) * 3, // 3.0M
+            file_count: 1,
+            dir_count: 0,
+            newest_mtime: 0,
+            oldest_mtime: i64::MAX,
+            newest_btime: None,
+        };
+        let mut data = get_fake_display_data(20);
+        data.initial.compare_sizes = true;
+        data.num_chars_needed_on_left_most = 5 * 2 + RATIO_SHOW_LENGTH + 2;
+
+        let s = format_string(&n, data.base_size, "┌─┴", "", false, &data);
+        assert_eq!(s, "4.0Ki 3.0Mi   768x ┌─┴ sparse.img");
+    }
+
+    #[test]
+    fn test_format_str_columns() {
+        let n = DisplayNode {
+            name: PathBuf::from("/many"),
+            size: 2_u64.pow(12),
+            children: vec![],
+            disk_size: 2_u64.pow(12),
+            apparent_size: 2_u64.pow(12),
+            file_count: 1200,
+            dir_count: 3,
+            newest_mtime: 0,
+            oldest_mtime: i64::MAX,
+            newest_btime: None,
+        };
+        let mut data = get_fake_display_data(20);
+        data.initial.columns = vec![Column::Disk, Column::Files, Column::Dirs, Column::Oldest];
+        data.column_widths = vec![5, 6, 2, 1];
+        data.num_chars_needed_on_left_most = 5 + 6 + 2 + 1 + 3;
+
+        let s = format_string(&n, data.base_size, "┌─┴", "", false, &data);
+        assert_eq!(s, "4.0Ki  1,200  3 - ┌─┴ many");
+    }
+
+    #[test]
+    fn test_format_str_screen_reader_filesystem_percent() {
+        let n = DisplayNode {
+            name: PathBuf::from("/short"),
+            size: 2_u64.pow(12),
+            children: vec![],
+            disk_size: 2_u64.pow(12),
+            apparent_size: 2_u64.pow(12),
+            file_count: 1,
+            dir_count: 0,
+            newest_mtime: 0,
+            oldest_mtime: i64::MAX,
+            newest_btime: None,
+        };
+        let mut data = get_fake_display_data(20);
+        data.initial.is_screen_reader = true;
+        data.initial.filesystem_percent = true;
+        data.initial.filesystems = vec![FilesystemInfo {
+            root: PathBuf::from("/"),
+            mount_point: Some(PathBuf::from("/")),
+            fs_type: Some("ext4".into()),
+            total: 2_u64.pow(15),
+            used: 2_u64.pow(14),
+            free: 2_u64.pow(14),
+            inodes_total: 1000,
+            inodes_used: 10,
+        }];
+
+        let s = format_string(&n, data.base_size, "", "3", false, &data);
+        assert_eq!(s, "short               3 4.0Ki 100%  12.5%");
+    }
+
+    #[test]
+    fn test_format_filesystem_info() {
+        let filesystem = FilesystemInfo {
+            root: PathBuf::from("/home/andy"),
+            mount_point: Some(PathBuf::from("/home")),
+            fs_type: Some("btrfs".into()),
+            total: 4 * 1024_u64.pow(3),
+            used: 1024_u64.pow(3),
+            free: 3 * 1024_u64.pow(3),
+            inodes_total: 4000,
+            inodes_used: 1000,
+        };
+        assert_eq!(
+            format_filesystem_info(&filesystem, ""),
+            "/home/andy on /home (btrfs): 1.0Gi used of 4.0Gi (25%), 3.0Gi free, \
+             1,000 of 4,000 inodes used (25%)"
+        );
+    }
+
+    #[test]
+    fn test_get_size_ratio() {
+        assert_eq!(get_size_ratio(0, 100), "-");
+        assert_eq!(get_size_ratio(4096, 4096), "1.00x");
+        assert_eq!(get_size_ratio(4096, 1000), "0.24x");
+        assert_eq!(get_size_ratio(1000, 12_500), "12x");
+        assert_eq!(get_size_ratio(1, 5000), ">999x");
+    }
+
+    #[test]
+    fn test_format_str_mount_point() {
+        let n = DisplayNode {
+            name: PathBuf::from("/data/nfs"),
+            size: 2_u64.pow(12),
+            children: vec![],
+            disk_size: 2_u64.pow(12),
+            apparent_size: 2_u64.pow(12),
+            file_count: 1,
+            dir_count: 0,
+            newest_mtime: 0,
+            oldest_mtime: i64::MAX,
+            newest_btime: None,
+        };
+        let mut data = get_fake_display_data(20);
+        data.initial.mount_point_types =
+            HashMap::from([(PathBuf::from("/data/nfs"), "nfs".into())]);
+
+        let s = format_string(&n, data.base_size, "┌─┴", "", false, &data);
+        assert_eq!(s, "4.0Ki ┌─┴ nfs [nfs]");
+    }
+
+    #[test]
+    fn test_format_str_ascii() {
+        let n = DisplayNode {
+            name: PathBuf::from("/short"),
+            size: 2_u64.pow(12),
+            children: vec![],
+            disk_size: 2_u64.pow(12),
+            apparent_size: 2_u64.pow(12),
+            file_count: 1,
+            dir_count: 0,
+            newest_mtime: 0,
+            oldest_mtime: i64::MAX,
+            newest_btime: None,
+        };
+        let mut data = get_fake_display_data(12);
+        data.initial.theme = Theme::from_config(&crate::theme::ThemeConfig {
+            ascii: Some(true),
+            ..Default::default()
+        });
+
+        let s = format_string(&n, data.base_size, "│ ├─┬", "##=", false, &data);
+        assert_eq!(s, "4.0Ki | |-+ short |##= | 100%");
+    }
+
     #[test]
     fn test_machine_readable_filecount() {
         assert_eq!(human_readable_number(1, "count"), "1");
@@ -656,11 +1168,19 @@ mod tests {
             name: PathBuf::from("/short"),
             size: 2_u64.pow(size),
             children: vec![],
+            disk_size: 2_u64.pow(size),
+            apparent_size: 2_u64.pow(size),
+            file_count: 1,
+            dir_count: 0,
+            newest_mtime: 0,
+            oldest_mtime: i64::MAX,
+            newest_btime: None,
         };
-        let first_size_bar = repeat_n(BLOCKS[0], 13).collect();
+        let first_size_bar = repeat_n(disp.initial.theme.bar_chars[0], 13).collect();
         let dd = DrawData {
             indent: "".into(),
             percent_bar: first_size_bar,
+            parent_size: disp.base_size,
             display_data: disp,
         };
         (dd, n)
@@ -1,10 +1,13 @@
 use std::cell::RefCell;
+use std::collections::HashMap;
 use std::path::PathBuf;
 
 use serde::ser::SerializeStruct;
 use serde::{Serialize, Serializer};
+use serde_json::{Value, json};
 
 use crate::display::human_readable_number;
+use crate::filesystem::{FilesystemInfo, find_filesystem};
 
 #[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Clone)]
 pub struct DisplayNode {
@@ -12,6 +15,14 @@ pub struct DisplayNode {
     pub size: u64,
     pub name: PathBuf,
     pub children: Vec<DisplayNode>,
+    pub disk_size: u64,
+    pub apparent_size: u64,
+    // As in Node, shown by --columns
+    pub file_count: u64,
+    pub dir_count: u64,
+    pub newest_mtime: i64,
+    pub oldest_mtime: i64,
+    pub newest_btime: Option<i64>,
 }
 
 impl DisplayNode {
@@ -49,10 +60,72 @@ impl Serialize for DisplayNode {
     {
         let readable_size = OUTPUT_TYPE
             .with(|output_type| human_readable_number(self.size, output_type.borrow().as_str()));
-        let mut state = serializer.serialize_struct("DisplayNode", 2)?;
+        let (readable_disk_size, readable_apparent_size) = OUTPUT_TYPE.with(|output_type| {
+            let output_type = output_type.borrow();
+            (
+                human_readable_number(self.disk_size, output_type.as_str()),
+                human_readable_number(self.apparent_size, output_type.as_str()),
+            )
+        });
+        let mut state = serializer.serialize_struct("DisplayNode", 5)?;
         state.serialize_field("size", &(readable_size))?;
+        state.serialize_field("disk_size", &(readable_disk_size))?;
+        state.serialize_field("apparent_size", &(readable_apparent_size))?;
         state.serialize_field("name", &self.name)?;
         state.serialize_field("children", &self.children)?;
         state.end()
     }
 }
+
+// With --percent-of-parent each node of the -j output gets its share of the total
+// and of its parent, the JSON children are in the same order as the tree
+pub fn add_percents(json: &mut Value, node: &DisplayNode, total_size: u64, parent_size: u64) {
+    json["percent"] = json!({
+        "of_total": get_percent(node.size, total_size),
+        "of_parent": get_percent(node.size, parent_size),
+    });
+    if let Some(children) = json["children"].as_array_mut() {
+        for (child_json, child) in children.iter_mut().zip(node.children.iter()) {
+            add_percents(child_json, child, total_size, node.size);
+        }
+    }
+}
+
+// The filesystem type of the mount points the walk crossed, as [type] after
+// their name in the tree
+pub fn add_fs_types(json: &mut Value, node: &DisplayNode, types: &HashMap<PathBuf, String>) {
+    if let Some(fs_type) = types.get(&node.name) {
+        json["fs_type"] = json!(fs_type);
+    }
+    if let Some(children) = json["children"].as_array_mut() {
+        for (child_json, child) in children.iter_mut().zip(node.children.iter()) {
+            add_fs_types(child_json, child, types);
+        }
+    }
+}
+
+// With --filesystem-percent each node of the -j output gets its share of the
+// filesystem it is on
+pub fn add_filesystem_percents(
+    json: &mut Value,
+    node: &DisplayNode,
+    filesystems: &[FilesystemInfo],
+) {
+    if let Some(filesystem) = find_filesystem(filesystems, &node.name) {
+        json["percent_of_filesystem"] = json!(get_percent(node.size, filesystem.total));
+    }
+    if let Some(children) = json["children"].as_array_mut() {
+        for (child_json, child) in children.iter_mut().zip(node.children.iter()) {
+            add_filesystem_percents(child_json, child, filesystems);
+        }
+    }
+}
+
+fn get_percent(size: u64, whole: u64) -> f64 {
+    if whole == 0 {
+        0.0
+    } else {
+        // Two decimals are plenty and keep the output readable
+        (size as f64 * 10000.0 / whole as f64).round() / 100.0
+    }
+}
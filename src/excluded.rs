@@ -0,0 +1,126 @@
+use serde::Serialize;
+use thousands::Separable;
+
+use crate::dir_walker::SkipReason;
+use crate::dir_walker::SkippedData;
+use crate::display::human_readable_number;
+use crate::filter_expr::FilterKind;
+
+// What each ignore option and filter left out of the totals, so it is clear
+// whether the tree shows everything. Sizes are bytes on disk.
+#[derive(Debug, Serialize)]
+pub struct ExcludedReport {
+    pub reasons: Vec<ExcludedCount>,
+    // Mount points not gone into, their size is unknown
+    pub other_filesystems: usize,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ExcludedCount {
+    pub reason: &'static str,
+    #[serde(skip)]
+    pub label: &'static str,
+    pub files: u64,
+    pub bytes: u64,
+}
+
+pub fn get_excluded_report(skipped: &SkippedData) -> ExcludedReport {
+    let mut reasons: Vec<_> = skipped.excluded.iter().collect();
+    reasons.sort_by_key(|(reason, _)| **reason);
+    ExcludedReport {
+        reasons: reasons
+            .into_iter()
+            .map(|(reason, (files, bytes))| {
+                let (name, label) = get_reason_names(reason);
+                ExcludedCount {
+                    reason: name,
+                    label,
+                    files: *files,
+                    bytes: *bytes,
+                }
+            })
+            .collect(),
+        other_filesystems: skipped.other_filesystems.len(),
+    }
+}
+
+// The JSON name and the label, as the option is written on the command line
+fn get_reason_names(reason: &SkipReason) -> (&'static str, &'static str) {
+    match reason {
+        SkipReason::IgnoredPath => ("ignored_path", "ignored paths -X"),
+        SkipReason::ExcludedGlob => ("exclude_glob", "globs --exclude"),
+        SkipReason::GitIgnored => ("gitignore", "gitignore"),
+        SkipReason::DustIgnored => ("dustignore", ".dustignore"),
+        SkipReason::Hidden => ("hidden", "hidden"),
+        SkipReason::Filtered(FilterKind::Where) => ("where", "--where"),
+        SkipReason::Filtered(FilterKind::Regex) => ("regex", "regex -e"),
+        SkipReason::Filtered(FilterKind::InvertRegex) => ("invert_regex", "regex -v"),
+        SkipReason::Filtered(FilterKind::Size) => ("size", "size"),
+        SkipReason::Filtered(FilterKind::Time) => ("time", "time"),
+        SkipReason::Filtered(FilterKind::Owner) => ("owner", "owner and permissions"),
+    }
+}
+
+pub fn draw_excluded_report(report: &ExcludedReport, output_format: &str) {
+    println!("{}", format_excluded_report(report, output_format));
+}
+
+fn format_excluded_report(report: &ExcludedReport, output_format: &str) -> String {
+    let mut parts: Vec<_> = report
+        .reasons
+        .iter()
+        .map(|r| {
+            let files = if r.files == 1 { "file" } else { "files" };
+            format!(
+                "{}: {} in {} {files}",
+                r.label,
+                human_readable_number(r.bytes, output_format),
+                r.files.separate_with_commas()
+            )
+        })
+        .collect();
+    if report.other_filesystems > 0 {
+        let mounts = if report.other_filesystems == 1 {
+            "mount"
+        } else {
+            "mounts"
+        };
+        parts.push(format!(
+            "other filesystems: {} {mounts}",
+            report.other_filesystems
+        ));
+    }
+    if parts.is_empty() {
+        "Excluded: nothing".into()
+    } else {
+        format!("Excluded: {}", parts.join("; "))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    #[test]
+    fn test_format_excluded_report() {
+        let mut skipped = SkippedData::default();
+        skipped.excluded.insert(
+            SkipReason::Filtered(FilterKind::InvertRegex),
+            (12, 3 * 1024),
+        );
+        skipped.excluded.insert(SkipReason::Hidden, (40_000, 2048));
+        skipped.other_filesystems.insert(PathBuf::from("/proc"));
+        skipped.other_filesystems.insert(PathBuf::from("/sys"));
+
+        let report = get_excluded_report(&skipped);
+        assert_eq!(report.reasons[0].reason, "hidden");
+        assert_eq!(
+            format_excluded_report(&report, ""),
+            "Excluded: hidden: 2.0Ki in 40,000 files; regex -v: 3.0Ki in 12 files; other filesystems: 2 mounts"
+        );
+
+        let report = get_excluded_report(&SkippedData::default());
+        assert_eq!(format_excluded_report(&report, ""), "Excluded: nothing");
+    }
+}
@@ -0,0 +1,356 @@
+use serde::Serialize;
+use std::collections::HashMap;
+use std::collections::HashSet;
+use std::path::Path;
+use std::path::PathBuf;
+
+use crate::utils::canonicalize_absolute_path;
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct MountInfo {
+    pub mount_point: PathBuf,
+    pub fs_type: String,
+}
+
+// Kernel filesystems with no real disk usage, walking them is slow or can hang
+pub static VIRTUAL_FS_TYPES: [&str; 20] = [
+    "autofs",
+    "binfmt_misc",
+    "bpf",
+    "cgroup",
+    "cgroup2",
+    "configfs",
+    "debugfs",
+    "devpts",
+    "devtmpfs",
+    "efivarfs",
+    "fusectl",
+    "hugetlbfs",
+    "mqueue",
+    "nsfs",
+    "proc",
+    "pstore",
+    "rpc_pipefs",
+    "securityfs",
+    "sysfs",
+    "tracefs",
+];
+
+#[derive(Debug, Clone, Serialize)]
+pub struct FilesystemInfo {
+    pub root: PathBuf,
+    pub mount_point: Option<PathBuf>,
+    pub fs_type: Option<String>,
+    pub total: u64,
+    pub used: u64,
+    pub free: u64,
+    pub inodes_total: u64,
+    pub inodes_used: u64,
+}
+
+// Sizes are in bytes: (total, used, free, inodes_total, inodes_used)
+type FilesystemUsage = (u64, u64, u64, u64, u64);
+
+pub fn get_filesystem_info<P: AsRef<Path>>(roots: &[P]) -> Vec<FilesystemInfo> {
+    let mount_table = read_mount_table();
+    roots
+        .iter()
+        .filter_map(|root| {
+            let root = root.as_ref();
+            let (total, used, free, inodes_total, inodes_used) = get_filesystem_usage(root)?;
+            let mount = find_mount(&mount_table, root);
+            Some(FilesystemInfo {
+                root: root.to_path_buf(),
+                mount_point: mount.map(|m| m.mount_point.clone()),
+                fs_type: mount.map(|m| m.fs_type.clone()),
+                total,
+                used,
+                free,
+                inodes_total,
+                inodes_used,
+            })
+        })
+        .collect()
+}
+
+// The mount holding `path` is the last mounted one with the longest matching mount point
+pub fn find_mount<'a>(mount_table: &'a [MountInfo], path: &Path) -> Option<&'a MountInfo> {
+    let absolute_path = canonicalize_absolute_path(std::path::absolute(path).ok()?);
+    mount_table
+        .iter()
+        .filter(|m| absolute_path.starts_with(&m.mount_point))
+        .max_by_key(|m| m.mount_point.components().count())
+}
+
+// The filesystem of the scanned root holding `path`. The '(total)' row only has a
+// filesystem if every input shares it
+pub fn find_filesystem<'a>(
+    filesystems: &'a [FilesystemInfo],
+    path: &Path,
+) -> Option<&'a FilesystemInfo> {
+    filesystems
+        .iter()
+        .filter(|fs| path.starts_with(&fs.root))
+        .max_by_key(|fs| fs.root.components().count())
+        .or_else(|| {
+            let first = filesystems.first()?;
+            filesystems
+                .iter()
+                .all(|fs| fs.mount_point == first.mount_point && fs.total == first.total)
+                .then_some(first)
+        })
+}
+
+// Explicitly included types win over the default virtual exclusions
+pub fn is_fs_type_allowed(fs_type: &str, include: &[String], exclude: &[String]) -> bool {
+    let is_included = include.iter().any(|t| t == fs_type);
+    (include.is_empty() || is_included)
+        && !exclude.iter().any(|t| t == fs_type)
+        && (is_included || !VIRTUAL_FS_TYPES.contains(&fs_type))
+}
+
+// The mount points of the types left out, beneath the scanned paths and as the
+// walk will reach them. The walk compares paths, st_dev does not match the mount
+// table for every filesystem (eg: btrfs subvolumes).
+pub fn get_excluded_mounts<P: AsRef<Path>>(
+    mount_table: &[MountInfo],
+    roots: &[P],
+    include: &[String],
+    exclude: &[String],
+) -> HashSet<PathBuf> {
+    // The last mount on a path hides the ones before it
+    let allowed: HashMap<_, _> = get_mounts_beneath(mount_table, roots)
+        .map(|(path, m)| (path, is_fs_type_allowed(&m.fs_type, include, exclude)))
+        .collect();
+    allowed
+        .into_iter()
+        .filter(|(_, is_allowed)| !is_allowed)
+        .map(|(path, _)| path)
+        .collect()
+}
+
+// Maps the path of each mount point beneath the scanned paths, as it will appear
+// in the tree, to its filesystem type
+pub fn get_mount_point_types<P: AsRef<Path>>(
+    mount_table: &[MountInfo],
+    roots: &[P],
+) -> HashMap<PathBuf, String> {
+    get_mounts_beneath(mount_table, roots)
+        .map(|(path, m)| (path, m.fs_type.clone()))
+        .collect()
+}
+
+// The mounts strictly beneath the scanned paths, with their path in the tree
+fn get_mounts_beneath<'a, P: AsRef<Path>>(
+    mount_table: &'a [MountInfo],
+    roots: &[P],
+) -> impl Iterator<Item = (PathBuf, &'a MountInfo)> {
+    let roots = get_absolute_roots(roots);
+    mount_table.iter().filter_map(move |m| {
+        roots.iter().find_map(|(root, absolute_root)| {
+            match m.mount_point.strip_prefix(absolute_root) {
+                Ok(rest) if !rest.as_os_str().is_empty() => Some((root.join(rest), m)),
+                _ => None,
+            }
+        })
+    })
+}
+
+fn get_absolute_roots<P: AsRef<Path>>(roots: &[P]) -> Vec<(PathBuf, PathBuf)> {
+    roots
+        .iter()
+        .filter_map(|r| {
+            let r = r.as_ref();
+            let absolute = canonicalize_absolute_path(std::path::absolute(r).ok()?);
+            Some((r.to_path_buf(), absolute))
+        })
+        .collect()
+}
+
+#[cfg(target_os = "linux")]
+pub fn read_mount_table() -> Vec<MountInfo> {
+    match std::fs::read_to_string("/proc/self/mountinfo") {
+        Ok(content) => content.lines().filter_map(parse_mountinfo_line).collect(),
+        Err(_) => vec![],
+    }
+}
+
+#[cfg(not(target_os = "linux"))]
+pub fn read_mount_table() -> Vec<MountInfo> {
+    vec![]
+}
+
+// Format is described in proc(5):
+// 36 35 98:0 /mnt1 /mnt2 rw,noatime master:1 - ext3 /dev/root rw,errors=continue
+#[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+fn parse_mountinfo_line(line: &str) -> Option<MountInfo> {
+    let mut fields = line.split(' ');
+    let mount_point = fields.nth(4)?;
+    // Skip the optional fields, they end with a single '-'
+    let mut fields = fields.skip_while(|f| *f != "-").skip(1);
+    let fs_type = fields.next()?;
+    Some(MountInfo {
+        mount_point: PathBuf::from(unescape_mount_path(mount_point)),
+        fs_type: fs_type.to_string(),
+    })
+}
+
+// Spaces, tabs, newlines and backslashes are written as octal escapes, eg: '\040'
+#[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+fn unescape_mount_path(path: &str) -> String {
+    let bytes = path.as_bytes();
+    let mut out = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        let escaped = bytes.get(i + 1..i + 4);
+        if bytes[i] == b'\\'
+            && let Some(digits) = escaped
+            && digits.iter().all(|d| (b'0'..=b'7').contains(d))
+        {
+            out.push(digits.iter().fold(0u8, |acc, d| (acc << 3) | (d - b'0')));
+            i += 4;
+        } else {
+            out.push(bytes[i]);
+            i += 1;
+        }
+    }
+    String::from_utf8_lossy(&out).into_owned()
+}
+
+#[cfg(target_family = "unix")]
+fn get_filesystem_usage(path: &Path) -> Option<FilesystemUsage> {
+    use std::ffi::CString;
+    use std::os::unix::ffi::OsStrExt;
+
+    let c_path = CString::new(path.as_os_str().as_bytes()).ok()?;
+    let mut stat: libc::statvfs = unsafe { std::mem::zeroed() };
+    // SAFETY: c_path is a valid nul terminated string and stat is a valid statvfs struct
+    if unsafe { libc::statvfs(c_path.as_ptr(), &mut stat) } != 0 {
+        return None;
+    }
+    let fragment_size = stat.f_frsize as u64;
+    let total = stat.f_blocks as u64 * fragment_size;
+    let used = (stat.f_blocks as u64).saturating_sub(stat.f_bfree as u64) * fragment_size;
+    // Like df, report the space available to unprivileged users
+    let free = stat.f_bavail as u64 * fragment_size;
+    let inodes_total = stat.f_files as u64;
+    let inodes_used = inodes_total.saturating_sub(stat.f_ffree as u64);
+    Some((total, used, free, inodes_total, inodes_used))
+}
+
+#[cfg(not(target_family = "unix"))]
+fn get_filesystem_usage(_path: &Path) -> Option<FilesystemUsage> {
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_mountinfo_line() {
+        let line = "36 35 98:0 /mnt1 /mnt2 rw,noatime master:1 - ext3 /dev/root rw,errors=continue";
+        assert_eq!(
+            parse_mountinfo_line(line),
+            Some(MountInfo {
+                mount_point: PathBuf::from("/mnt2"),
+                fs_type: "ext3".to_string(),
+            })
+        );
+
+        let line = "23 28 0:22 / /proc rw,relatime - proc proc rw";
+        assert_eq!(
+            parse_mountinfo_line(line).map(|m| m.fs_type),
+            Some("proc".to_string())
+        );
+
+        assert_eq!(parse_mountinfo_line("not a mount line"), None);
+    }
+
+    #[test]
+    fn test_unescape_mount_path() {
+        assert_eq!(unescape_mount_path("/mnt/my\\040disk"), "/mnt/my disk");
+        assert_eq!(
+            unescape_mount_path("/mnt/back\\134slash"),
+            "/mnt/back\\slash"
+        );
+        assert_eq!(unescape_mount_path("/plain"), "/plain");
+        assert_eq!(unescape_mount_path("/trailing\\"), "/trailing\\");
+    }
+
+    #[test]
+    fn test_find_mount_picks_longest_mount_point() {
+        let table = get_mount_table();
+        let found = find_mount(&table, Path::new("/home/user/projects"));
+        assert_eq!(found.map(|m| m.fs_type.as_str()), Some("btrfs"));
+    }
+
+    fn get_mount_table() -> Vec<MountInfo> {
+        vec![
+            MountInfo {
+                mount_point: PathBuf::from("/"),
+                fs_type: "ext4".to_string(),
+            },
+            MountInfo {
+                mount_point: PathBuf::from("/home"),
+                fs_type: "btrfs".to_string(),
+            },
+            MountInfo {
+                mount_point: PathBuf::from("/home/user/nfs"),
+                fs_type: "nfs".to_string(),
+            },
+            MountInfo {
+                mount_point: PathBuf::from("/proc"),
+                fs_type: "proc".to_string(),
+            },
+        ]
+    }
+
+    #[test]
+    fn test_is_fs_type_allowed() {
+        let none: Vec<String> = vec![];
+        assert!(is_fs_type_allowed("ext4", &none, &none));
+        assert!(!is_fs_type_allowed("proc", &none, &none));
+        assert!(!is_fs_type_allowed("nfs", &none, &["nfs".to_string()]));
+        assert!(!is_fs_type_allowed("ext4", &["nfs".to_string()], &none));
+        // Including a virtual type overrides the default exclusion
+        assert!(is_fs_type_allowed("proc", &["proc".to_string()], &none));
+    }
+
+    #[test]
+    fn test_get_excluded_mounts() {
+        let table = get_mount_table();
+        let none: Vec<String> = vec![];
+        let nfs = vec!["nfs".to_string()];
+
+        // Only mounts beneath the scanned path are excluded
+        assert_eq!(
+            get_excluded_mounts(&table, &["/home"], &none, &nfs),
+            HashSet::from([PathBuf::from("/home/user/nfs")])
+        );
+        assert_eq!(
+            get_excluded_mounts(&table, &["/"], &none, &nfs),
+            HashSet::from([PathBuf::from("/home/user/nfs"), PathBuf::from("/proc")])
+        );
+        // Unless it is the scanned path
+        assert_eq!(
+            get_excluded_mounts(&table, &["/proc"], &none, &nfs),
+            HashSet::new()
+        );
+        // Everything not included, the filesystem of the scanned path is walked anyway
+        assert_eq!(
+            get_excluded_mounts(&table, &["/"], &["btrfs".to_string()], &none),
+            HashSet::from([PathBuf::from("/home/user/nfs"), PathBuf::from("/proc")])
+        );
+    }
+
+    #[test]
+    fn test_get_mount_point_types() {
+        let table = get_mount_table();
+        let types = get_mount_point_types(&table, &["/home"]);
+        assert_eq!(
+            types,
+            HashMap::from([(PathBuf::from("/home/user/nfs"), "nfs".to_string())])
+        );
+    }
+}
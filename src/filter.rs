@@ -1,31 +1,52 @@
 use stfu8::encode_u8;
 
+use crate::cli::FlatDepth;
+use crate::cli::SortBy;
 use crate::display::get_printable_name;
+use crate::display::get_size_str;
 use crate::display_node::DisplayNode;
 use crate::node::FileTime;
 use crate::node::Node;
+use crate::utils::natural_cmp;
 use std::collections::BinaryHeap;
 use std::collections::HashMap;
 use std::collections::HashSet;
 use std::path::Path;
 use std::path::PathBuf;
 
+// The smallest entries shown: -z 10M, 1% (of the total) or 5%p (of the parent)
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum MinSize {
+    Bytes(usize),
+    PercentOfTotal(f64),
+    PercentOfParent(f64),
+}
+
 pub struct AggregateData {
-    pub min_size: Option<usize>,
+    pub min_size: Option<MinSize>,
     pub only_dir: bool,
     pub only_file: bool,
     pub number_of_lines: usize,
     pub depth: usize,
     pub using_a_filter: bool,
     pub short_paths: bool,
+    pub show_remainder: bool,
+    // As for display, the sizes of the remainder entries are written in their name
+    pub output_format: String,
+    // Limits on the entries shown in each directory, the biggest are kept
+    pub per_dir: Option<usize>,
+    // The order of the entries shown, they are still picked by size
+    pub sort_by: SortBy,
 }
 
 pub fn get_biggest(
     top_level_nodes: Vec<Node>,
-    display_data: AggregateData,
+    mut display_data: AggregateData,
     by_filetime: &Option<FileTime>,
     keep_collapsed: HashSet<PathBuf>,
 ) -> DisplayNode {
+    resolve_percent_of_total(&mut display_data, &top_level_nodes);
+
     let mut heap = BinaryHeap::new();
     let number_top_level_nodes = top_level_nodes.len();
     let root;
@@ -51,13 +72,98 @@ pub fn get_biggest(
         heap = add_children(&display_data, &root, heap);
     }
 
-    fill_remaining_lines(heap, &root, display_data, keep_collapsed)
+    fill_remaining_lines(heap, &root, display_data, by_filetime, keep_collapsed)
+}
+
+// A percent of the total is the same size for every directory
+fn resolve_percent_of_total(display_data: &mut AggregateData, top_level_nodes: &[Node]) {
+    if let Some(MinSize::PercentOfTotal(percent)) = display_data.min_size {
+        let total = top_level_nodes.iter().map(|node| node.size).sum::<u64>();
+        display_data.min_size = Some(MinSize::Bytes((total as f64 * percent / 100.0) as usize));
+    }
+}
+
+// The directories at the given depth (or down to it) as children of '(total)', like
+// du -d N | sort -h. The biggest are picked and then put in the requested order
+pub fn get_flat(
+    top_level_nodes: Vec<Node>,
+    mut display_data: AggregateData,
+    flat: FlatDepth,
+    by_filetime: &Option<FileTime>,
+) -> DisplayNode {
+    resolve_percent_of_total(&mut display_data, &top_level_nodes);
+    if display_data.depth == usize::MAX {
+        display_data.depth = 1;
+    }
+
+    let mut listed = vec![];
+    for node in top_level_nodes.iter() {
+        collect_flat(&display_data, flat, node, &mut listed);
+    }
+    listed.sort_by(|lhs, rhs| lhs.cmp(rhs).reverse());
+    listed.truncate(display_data.number_of_lines);
+    sort_nodes(&mut listed, display_data.sort_by);
+
+    let children = listed
+        .into_iter()
+        .map(|node| DisplayNode {
+            name: node.name.clone(),
+            size: node.size,
+            children: vec![],
+            disk_size: node.disk_size,
+            apparent_size: node.apparent_size,
+            file_count: node.file_count,
+            dir_count: node.dir_count,
+            newest_mtime: node.newest_mtime,
+            oldest_mtime: node.oldest_mtime,
+            newest_btime: node.newest_btime,
+        })
+        .collect();
+    let size = if by_filetime.is_some() {
+        top_level_nodes.iter().map(|node| node.size).max()
+    } else {
+        Some(top_level_nodes.iter().map(|node| node.size).sum())
+    };
+    let total = total_node_builder(size.unwrap_or(0), top_level_nodes);
+    build_display_node(children, &total, display_data.sort_by)
+}
+
+fn collect_flat<'a>(
+    display_data: &AggregateData,
+    flat: FlatDepth,
+    node: &'a Node,
+    listed: &mut Vec<&'a Node>,
+) {
+    let at_depth = match flat {
+        FlatDepth::Exact => node.depth == display_data.depth,
+        FlatDepth::Upto => node.depth <= display_data.depth,
+    };
+    // Directories are told apart by the walk, not by the disk
+    if at_depth && (!node.children.is_empty() || node.dir_count > 0) {
+        listed.push(node);
+    }
+    if node.depth < display_data.depth {
+        for child in get_children_to_show(display_data, node) {
+            collect_flat(display_data, flat, child, listed);
+        }
+    }
 }
 
 fn total_node_builder(size: u64, children: Vec<Node>) -> Node {
     Node {
         name: PathBuf::from("(total)"),
         size,
+        disk_size: children.iter().map(|c| c.disk_size).sum(),
+        apparent_size: children.iter().map(|c| c.apparent_size).sum(),
+        file_count: children.iter().map(|c| c.file_count).sum(),
+        dir_count: children.iter().map(|c| c.dir_count).sum(),
+        newest_mtime: children.iter().map(|c| c.newest_mtime).max().unwrap_or(0),
+        oldest_mtime: children
+            .iter()
+            .map(|c| c.oldest_mtime)
+            .min()
+            .unwrap_or(i64::MAX),
+        newest_btime: children.iter().map(|c| c.newest_btime).max().flatten(),
         children,
         inode_device: None,
         depth: 0,
@@ -68,44 +174,76 @@ pub fn fill_remaining_lines<'a>(
     mut heap: BinaryHeap<&'a Node>,
     root: &'a Node,
     display_data: AggregateData,
+    by_filetime: &Option<FileTime>,
     keep_collapsed: HashSet<PathBuf>,
 ) -> DisplayNode {
     let mut allowed_nodes = HashMap::new();
+    // Adds the line, true when its children may be shown too
+    let allow = |allowed_nodes: &mut HashMap<&'a Path, &'a Node>, line: &'a Node| {
+        // If we are not doing only_file OR if we are doing
+        // only_file and it has no children (ie is a file not a dir)
+        if !display_data.only_file || line.children.is_empty() {
+            allowed_nodes.insert(line.name.as_path(), line);
+        }
+        !keep_collapsed.contains(&line.name)
+    };
 
-    while allowed_nodes.len() < display_data.number_of_lines {
-        let line = heap.pop();
-        match line {
-            Some(line) => {
-                // If we are not doing only_file OR if we are doing
-                // only_file and it has no children (ie is a file not a dir)
-                if !display_data.only_file || line.children.is_empty() {
-                    allowed_nodes.insert(line.name.as_path(), line);
-                }
-                if !keep_collapsed.contains(&line.name) {
-                    heap = add_children(&display_data, line, heap);
+    if display_data.per_dir.is_some() {
+        // Every directory down to the depth shows its own biggest entries, the
+        // number of lines does not apply so a big directory can not use them up
+        let mut lines = heap.into_vec();
+        while let Some(line) = lines.pop() {
+            if allow(&mut allowed_nodes, line) {
+                lines.extend(get_children_in_depth(&display_data, line));
+            }
+        }
+    } else {
+        while allowed_nodes.len() < display_data.number_of_lines {
+            let line = heap.pop();
+            match line {
+                Some(line) => {
+                    if allow(&mut allowed_nodes, line) {
+                        heap = add_children(&display_data, line, heap);
+                    }
                 }
+                None => break,
             }
-            None => break,
         }
     }
 
     if display_data.only_file {
-        flat_rebuilder(allowed_nodes, root)
+        flat_rebuilder(allowed_nodes, root, display_data.sort_by)
     } else {
-        recursive_rebuilder(&allowed_nodes, root)
+        let remainder = display_data
+            .show_remainder
+            .then_some((by_filetime, display_data.output_format.as_str()));
+        recursive_rebuilder(&allowed_nodes, root, remainder, display_data.sort_by)
     }
 }
 
 fn add_children<'a>(
     display_data: &AggregateData,
     file_or_folder: &'a Node,
-    heap: BinaryHeap<&'a Node>,
+    mut heap: BinaryHeap<&'a Node>,
 ) -> BinaryHeap<&'a Node> {
-    if display_data.depth > file_or_folder.depth {
-        always_add_children(display_data, file_or_folder, heap)
-    } else {
-        heap
+    heap.extend(get_children_in_depth(display_data, file_or_folder));
+    heap
+}
+
+// The per directory limits apply here, not to the top level paths below '(total)'
+fn get_children_in_depth<'a>(
+    display_data: &AggregateData,
+    file_or_folder: &'a Node,
+) -> Vec<&'a Node> {
+    if display_data.depth <= file_or_folder.depth {
+        return vec![];
     }
+    let mut children: Vec<_> = get_children_to_show(display_data, file_or_folder).collect();
+    if let Some(per_dir) = display_data.per_dir {
+        children.sort_by(|lhs, rhs| lhs.cmp(rhs).reverse());
+        children.truncate(per_dir);
+    }
+    children
 }
 
 fn always_add_children<'a>(
@@ -113,56 +251,187 @@ fn always_add_children<'a>(
     file_or_folder: &'a Node,
     mut heap: BinaryHeap<&'a Node>,
 ) -> BinaryHeap<&'a Node> {
-    heap.extend(
-        file_or_folder
-            .children
-            .iter()
-            .filter(|c| match display_data.min_size {
-                Some(ms) => c.size > ms as u64,
-                None => !display_data.using_a_filter || c.name.is_file() || c.size > 0,
-            })
-            .filter(|c| {
-                if display_data.only_dir {
-                    c.name.is_dir()
-                } else {
-                    true
-                }
-            }),
-    );
+    heap.extend(get_children_to_show(display_data, file_or_folder));
     heap
 }
 
-// Finds children of current, if in allowed_nodes adds them as children to new DisplayNode
-fn recursive_rebuilder(allowed_nodes: &HashMap<&Path, &Node>, current: &Node) -> DisplayNode {
-    let new_children: Vec<_> = current
+fn get_children_to_show<'a>(
+    display_data: &AggregateData,
+    file_or_folder: &'a Node,
+) -> impl Iterator<Item = &'a Node> {
+    file_or_folder
+        .children
+        .iter()
+        .filter(move |c| match display_data.min_size {
+            Some(MinSize::Bytes(ms)) => c.size > ms as u64,
+            Some(MinSize::PercentOfParent(percent)) => {
+                c.size as f64 * 100.0 >= file_or_folder.size as f64 * percent
+            }
+            // Already turned into bytes by get_biggest
+            Some(MinSize::PercentOfTotal(_)) | None => {
+                !display_data.using_a_filter || c.name.is_file() || c.size > 0
+            }
+        })
+        .filter(|c| {
+            if display_data.only_dir {
+                c.name.is_dir()
+            } else {
+                true
+            }
+        })
+}
+
+// Finds children of current, if in allowed_nodes adds them as children to new DisplayNode.
+// With `remainder` the children left out of a shown directory are added up in one more
+// child, it is always the last one
+fn recursive_rebuilder(
+    allowed_nodes: &HashMap<&Path, &Node>,
+    current: &Node,
+    remainder: Option<(&Option<FileTime>, &str)>,
+    sort_by: SortBy,
+) -> DisplayNode {
+    let (mut shown, hidden): (Vec<_>, Vec<_>) = current
         .children
         .iter()
-        .filter(|c| allowed_nodes.contains_key(c.name.as_path()))
-        .map(|c| recursive_rebuilder(allowed_nodes, c))
+        .partition(|c| allowed_nodes.contains_key(c.name.as_path()));
+    sort_nodes(&mut shown, sort_by);
+    let new_children: Vec<_> = shown
+        .into_iter()
+        .map(|c| recursive_rebuilder(allowed_nodes, c, remainder, sort_by))
         .collect();
 
-    build_display_node(new_children, current)
+    // Directories with no children shown are collapsed, a remainder would be all of them.
+    // Empty entries, such as those a filter left out, add nothing so are not counted
+    let hidden: Vec<_> = hidden.into_iter().filter(|c| c.size > 0).collect();
+    let remainder_node = match remainder {
+        Some((by_filetime, output_format)) if !new_children.is_empty() && !hidden.is_empty() => {
+            Some(build_remainder_node(&hidden, by_filetime, output_format))
+        }
+        _ => None,
+    };
+
+    let mut display_node = build_display_node(new_children, current, sort_by);
+    display_node.children.extend(remainder_node);
+    display_node
+}
+
+fn build_remainder_node(
+    hidden: &[&Node],
+    by_filetime: &Option<FileTime>,
+    output_format: &str,
+) -> DisplayNode {
+    let size = if by_filetime.is_some() {
+        hidden.iter().map(|node| node.size).max().unwrap_or(0)
+    } else {
+        hidden.iter().map(|node| node.size).sum()
+    };
+    let size_str = get_size_str(size, by_filetime.is_some(), output_format);
+    DisplayNode {
+        name: PathBuf::from(format!("({} more: {size_str})", hidden.len())),
+        size,
+        children: vec![],
+        disk_size: hidden.iter().map(|node| node.disk_size).sum(),
+        apparent_size: hidden.iter().map(|node| node.apparent_size).sum(),
+        file_count: hidden.iter().map(|node| node.file_count).sum(),
+        dir_count: hidden.iter().map(|node| node.dir_count).sum(),
+        newest_mtime: hidden
+            .iter()
+            .map(|node| node.newest_mtime)
+            .max()
+            .unwrap_or(0),
+        oldest_mtime: hidden
+            .iter()
+            .map(|node| node.oldest_mtime)
+            .min()
+            .unwrap_or(i64::MAX),
+        newest_btime: hidden.iter().map(|node| node.newest_btime).max().flatten(),
+    }
 }
 
 // Applies all allowed nodes as children to current node
-fn flat_rebuilder(allowed_nodes: HashMap<&Path, &Node>, current: &Node) -> DisplayNode {
-    let new_children: Vec<DisplayNode> = allowed_nodes
-        .into_values()
+fn flat_rebuilder(
+    allowed_nodes: HashMap<&Path, &Node>,
+    current: &Node,
+    sort_by: SortBy,
+) -> DisplayNode {
+    let mut nodes: Vec<_> = allowed_nodes.into_values().collect();
+    sort_nodes(&mut nodes, sort_by);
+    let new_children: Vec<DisplayNode> = nodes
+        .into_iter()
         .map(|v| DisplayNode {
             name: v.name.clone(),
             size: v.size,
             children: vec![],
+            disk_size: v.disk_size,
+            apparent_size: v.apparent_size,
+            file_count: v.file_count,
+            dir_count: v.dir_count,
+            newest_mtime: v.newest_mtime,
+            oldest_mtime: v.oldest_mtime,
+            newest_btime: v.newest_btime,
         })
         .collect::<Vec<DisplayNode>>();
-    build_display_node(new_children, current)
+    build_display_node(new_children, current, sort_by)
+}
+
+// Biggest first, like the sizes: the tree is drawn from the last child by default
+fn sort_nodes(nodes: &mut [&Node], sort_by: SortBy) {
+    let name = |node: &Node| node.name.to_string_lossy().into_owned();
+    let extension = |node: &Node| {
+        node.name
+            .extension()
+            .map(|e| e.to_string_lossy().to_lowercase())
+            .unwrap_or_default()
+    };
+    match sort_by {
+        SortBy::Size => nodes.sort_by(|lhs, rhs| rhs.cmp(lhs)),
+        SortBy::Name => nodes.sort_by_cached_key(|node| std::cmp::Reverse(NaturalKey(name(node)))),
+        SortBy::Mtime => nodes.sort_by(|lhs, rhs| {
+            (rhs.newest_mtime.cmp(&lhs.newest_mtime)).then_with(|| rhs.cmp(lhs))
+        }),
+        SortBy::Count => nodes
+            .sort_by(|lhs, rhs| (rhs.file_count.cmp(&lhs.file_count)).then_with(|| rhs.cmp(lhs))),
+        SortBy::Extension => nodes.sort_by_cached_key(|node| {
+            std::cmp::Reverse((NaturalKey(extension(node)), NaturalKey(name(node))))
+        }),
+    }
+}
+
+#[derive(PartialEq, Eq)]
+struct NaturalKey(String);
+
+impl Ord for NaturalKey {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        natural_cmp(&self.0, &other.0)
+    }
 }
 
-fn build_display_node(mut new_children: Vec<DisplayNode>, current: &Node) -> DisplayNode {
-    new_children.sort_by(|lhs, rhs| lhs.cmp(rhs).reverse());
+impl PartialOrd for NaturalKey {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+fn build_display_node(
+    mut new_children: Vec<DisplayNode>,
+    current: &Node,
+    sort_by: SortBy,
+) -> DisplayNode {
+    // Other orders were set by sort_nodes
+    if sort_by == SortBy::Size {
+        new_children.sort_by(|lhs, rhs| lhs.cmp(rhs).reverse());
+    }
     DisplayNode {
         name: current.name.clone(),
         size: current.size,
         children: new_children,
+        disk_size: current.disk_size,
+        apparent_size: current.apparent_size,
+        file_count: current.file_count,
+        dir_count: current.dir_count,
+        newest_mtime: current.newest_mtime,
+        oldest_mtime: current.oldest_mtime,
+        newest_btime: current.newest_btime,
     }
 }
 
@@ -202,6 +471,13 @@ fn handle_duplicate_top_level_names(top_level_nodes: Vec<Node>, short_paths: boo
                         let n = Node {
                             name: PathBuf::from(format!("{current_node}({parent})")),
                             size: node.size,
+                            disk_size: node.disk_size,
+                            apparent_size: node.apparent_size,
+                            file_count: node.file_count,
+                            dir_count: node.dir_count,
+                            newest_mtime: node.newest_mtime,
+                            oldest_mtime: node.oldest_mtime,
+                            newest_btime: node.newest_btime,
                             children: node.children.clone(),
                             inode_device: node.inode_device,
                             depth: node.depth,
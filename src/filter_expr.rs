@@ -0,0 +1,726 @@
+use regex::bytes::Regex;
+use std::fmt;
+use std::path::Path;
+
+use crate::cli::MatchOn;
+use crate::config::DAY_SECONDS;
+use crate::config::convert_size;
+use crate::dir_walker::Operator;
+use crate::node::FileTime;
+use crate::owner_filter::ModeMatch;
+use crate::owner_filter::parse_group;
+use crate::owner_filter::parse_mode;
+use crate::owner_filter::parse_user;
+use crate::time_filter::parse_duration;
+use crate::time_filter::parse_time_point;
+
+// A filter is parsed once from a --where expression, eg:
+//   size > 100M and (ext in [log, gz] or mtime > 90d) and not path ~ "/keep/"
+// The -e, -v, -M, -A, -y, --user, --group and --perm flags are turned into
+// expressions of the same kind, a file is kept when all of them match.
+#[derive(Debug)]
+pub enum FilterExpr {
+    And(Box<FilterExpr>, Box<FilterExpr>),
+    Or(Box<FilterExpr>, Box<FilterExpr>),
+    Not(Box<FilterExpr>),
+    // Inclusive bounds in bytes
+    Size(Option<u64>, Option<u64>),
+    Time(FileTime, Operator, i64),
+    // Regexes work on the raw bytes of a path so non UTF-8 names still match
+    PathMatches(Regex),
+    NameMatches(Regex),
+    // The path below the top level directory being walked
+    RelativePathMatches(Regex),
+    // Lower case and without the leading dot
+    ExtensionIn(Vec<String>),
+    // Numeric ids, names are resolved when parsing
+    UserIn(Vec<u32>),
+    GroupIn(Vec<u32>),
+    Mode(ModeMatch),
+}
+
+// The option a filter came from, so what each one left out can be reported
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum FilterKind {
+    Where,
+    Regex,
+    InvertRegex,
+    Size,
+    Time,
+    Owner,
+}
+
+// What a filter is matched against, the size is the one being displayed
+pub struct Candidate<'a> {
+    pub path: &'a Path,
+    // The top level directory the path was found under
+    pub root: &'a Path,
+    pub size: u64,
+    pub times: (i64, i64, i64, Option<i64>),
+    // Owner, group and mode, None where the platform has no such thing
+    pub ownership: Option<(u32, u32, u32)>,
+}
+
+impl FilterExpr {
+    pub fn parse(input: &str, now: i64) -> Result<FilterExpr, ParseError> {
+        let tokens = tokenize(input)?;
+        let mut parser = Parser {
+            tokens,
+            next: 0,
+            end: input.chars().count(),
+            now,
+        };
+        let expr = parser.parse_or()?;
+        match parser.peek() {
+            None => Ok(expr),
+            Some((token, position)) => Err(ParseError::new(
+                format!("unexpected {token} after the end of the expression"),
+                *position,
+            )),
+        }
+    }
+
+    // Matches when any of the regexes matches the chosen part of the path, as -e does
+    pub fn any_matches(regexes: Vec<Regex>, match_on: MatchOn) -> Option<FilterExpr> {
+        let make = match match_on {
+            MatchOn::Path => FilterExpr::PathMatches,
+            MatchOn::Name => FilterExpr::NameMatches,
+            MatchOn::Relative => FilterExpr::RelativePathMatches,
+        };
+        regexes
+            .into_iter()
+            .map(make)
+            .reduce(|a, b| FilterExpr::Or(Box::new(a), Box::new(b)))
+    }
+
+    pub fn and(self, other: FilterExpr) -> FilterExpr {
+        FilterExpr::And(Box::new(self), Box::new(other))
+    }
+
+    pub fn negate(self) -> FilterExpr {
+        FilterExpr::Not(Box::new(self))
+    }
+
+    // Matching on paths alone avoids reading the metadata of every file
+    pub fn needs_metadata(&self) -> bool {
+        match self {
+            FilterExpr::And(a, b) | FilterExpr::Or(a, b) => {
+                a.needs_metadata() || b.needs_metadata()
+            }
+            FilterExpr::Not(a) => a.needs_metadata(),
+            FilterExpr::Size(..) | FilterExpr::Time(..) => true,
+            FilterExpr::PathMatches(_)
+            | FilterExpr::NameMatches(_)
+            | FilterExpr::RelativePathMatches(_)
+            | FilterExpr::ExtensionIn(_)
+            | FilterExpr::UserIn(_)
+            | FilterExpr::GroupIn(_)
+            | FilterExpr::Mode(_) => false,
+        }
+    }
+
+    // Ownership is read separately from the sizes and times
+    pub fn needs_ownership(&self) -> bool {
+        match self {
+            FilterExpr::And(a, b) | FilterExpr::Or(a, b) => {
+                a.needs_ownership() || b.needs_ownership()
+            }
+            FilterExpr::Not(a) => a.needs_ownership(),
+            FilterExpr::UserIn(_) | FilterExpr::GroupIn(_) | FilterExpr::Mode(_) => true,
+            _ => false,
+        }
+    }
+
+    // Sizes, owners and modes are only checked against files, directories add
+    // up whichever of their files match
+    pub fn applies_to_dirs(&self) -> bool {
+        match self {
+            FilterExpr::And(a, b) | FilterExpr::Or(a, b) => {
+                a.applies_to_dirs() && b.applies_to_dirs()
+            }
+            FilterExpr::Not(a) => a.applies_to_dirs(),
+            FilterExpr::Size(..)
+            | FilterExpr::UserIn(_)
+            | FilterExpr::GroupIn(_)
+            | FilterExpr::Mode(_) => false,
+            _ => true,
+        }
+    }
+
+    pub fn is_match(&self, candidate: &Candidate) -> bool {
+        match self {
+            FilterExpr::And(a, b) => a.is_match(candidate) && b.is_match(candidate),
+            FilterExpr::Or(a, b) => a.is_match(candidate) || b.is_match(candidate),
+            FilterExpr::Not(a) => !a.is_match(candidate),
+            FilterExpr::Size(min, max) => {
+                min.is_none_or(|m| candidate.size >= m) && max.is_none_or(|m| candidate.size <= m)
+            }
+            FilterExpr::Time(file_time, operator, bound_time) => {
+                let (modified, accessed, changed, created) = candidate.times;
+                let actual_time = match file_time {
+                    FileTime::Modified => modified,
+                    FileTime::Accessed => accessed,
+                    FileTime::Changed => changed,
+                    // A file without a birth time never matches a bound on it
+                    FileTime::Created => match created {
+                        Some(created) => created,
+                        None => return false,
+                    },
+                };
+                match operator {
+                    Operator::Equal => {
+                        actual_time >= *bound_time && actual_time < *bound_time + DAY_SECONDS
+                    }
+                    Operator::GreaterThan => actual_time >= *bound_time,
+                    Operator::LessThan => actual_time <= *bound_time,
+                }
+            }
+            FilterExpr::PathMatches(regex) => {
+                regex.is_match(candidate.path.as_os_str().as_encoded_bytes())
+            }
+            FilterExpr::NameMatches(regex) => candidate
+                .path
+                .file_name()
+                .is_some_and(|name| regex.is_match(name.as_encoded_bytes())),
+            FilterExpr::RelativePathMatches(regex) => {
+                let relative = candidate
+                    .path
+                    .strip_prefix(candidate.root)
+                    .unwrap_or(candidate.path);
+                regex.is_match(relative.as_os_str().as_encoded_bytes())
+            }
+            FilterExpr::ExtensionIn(extensions) => {
+                candidate.path.extension().is_some_and(|extension| {
+                    let extension = extension.to_string_lossy().to_lowercase();
+                    extensions.contains(&extension)
+                })
+            }
+            FilterExpr::UserIn(users) => candidate
+                .ownership
+                .is_some_and(|(uid, _, _)| users.contains(&uid)),
+            FilterExpr::GroupIn(groups) => candidate
+                .ownership
+                .is_some_and(|(_, gid, _)| groups.contains(&gid)),
+            FilterExpr::Mode(mode) => candidate
+                .ownership
+                .is_some_and(|(_, _, bits)| mode.is_match(bits)),
+        }
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub struct ParseError {
+    pub message: String,
+    // In characters from the start of the expression
+    pub position: usize,
+}
+
+impl ParseError {
+    fn new(message: String, position: usize) -> ParseError {
+        ParseError { message, position }
+    }
+
+    // The message followed by the expression with the position marked below it
+    pub fn describe(&self, input: &str) -> String {
+        format!(
+            "{}\n  {input}\n  {}^",
+            self.message,
+            " ".repeat(self.position)
+        )
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Word(String),
+    Quoted(String),
+    Symbol(&'static str),
+}
+
+impl fmt::Display for Token {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Token::Word(w) => write!(f, "'{w}'"),
+            Token::Quoted(q) => write!(f, "\"{q}\""),
+            Token::Symbol(s) => write!(f, "'{s}'"),
+        }
+    }
+}
+
+static SYMBOLS: [&str; 14] = [
+    "==", "!=", "<=", ">=", "!~", "=", "<", ">", "~", "(", ")", "[", "]", ",",
+];
+
+fn tokenize(input: &str) -> Result<Vec<(Token, usize)>, ParseError> {
+    let chars: Vec<char> = input.chars().collect();
+    let mut tokens = vec![];
+    let mut i = 0;
+    while i < chars.len() {
+        let c = chars[i];
+        if c.is_whitespace() {
+            i += 1;
+        } else if c == '"' || c == '\'' {
+            // Only the quote and the backslash can be escaped, so regexes keep their backslashes
+            let start = i;
+            let mut text = String::new();
+            i += 1;
+            loop {
+                match chars.get(i) {
+                    None => {
+                        return Err(ParseError::new("unterminated quoted string".into(), start));
+                    }
+                    Some('\\') if matches!(chars.get(i + 1), Some(&n) if n == c || n == '\\') => {
+                        text.push(chars[i + 1]);
+                        i += 2;
+                    }
+                    Some(&q) if q == c => {
+                        i += 1;
+                        break;
+                    }
+                    Some(&other) => {
+                        text.push(other);
+                        i += 1;
+                    }
+                }
+            }
+            tokens.push((Token::Quoted(text), start));
+        } else if let Some(symbol) = SYMBOLS.iter().find(|s| {
+            s.chars()
+                .enumerate()
+                .all(|(n, sc)| chars.get(i + n) == Some(&sc))
+        }) {
+            let normalized = if *symbol == "==" { "=" } else { symbol };
+            tokens.push((Token::Symbol(normalized), i));
+            i += symbol.len();
+        } else {
+            let start = i;
+            let mut word = String::new();
+            while let Some(&c) = chars.get(i) {
+                if c.is_whitespace() || "()[],=!<>~\"'".contains(c) {
+                    break;
+                }
+                word.push(c);
+                i += 1;
+            }
+            if word.is_empty() {
+                return Err(ParseError::new(format!("unexpected character '{c}'"), i));
+            }
+            tokens.push((Token::Word(word), start));
+        }
+    }
+    Ok(tokens)
+}
+
+struct Parser {
+    tokens: Vec<(Token, usize)>,
+    next: usize,
+    end: usize,
+    now: i64,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<&(Token, usize)> {
+        self.tokens.get(self.next)
+    }
+
+    fn position(&self) -> usize {
+        self.peek().map_or(self.end, |(_, p)| *p)
+    }
+
+    fn take(&mut self, expected: &str) -> Result<(Token, usize), ParseError> {
+        match self.tokens.get(self.next) {
+            Some(token) => {
+                self.next += 1;
+                Ok(token.clone())
+            }
+            None => Err(ParseError::new(
+                format!("expected {expected} but the expression ended"),
+                self.end,
+            )),
+        }
+    }
+
+    fn is_keyword(&self, keyword: &str) -> bool {
+        matches!(self.peek(), Some((Token::Word(w), _)) if w.eq_ignore_ascii_case(keyword))
+    }
+
+    fn is_symbol(&self, symbol: &str) -> bool {
+        matches!(self.peek(), Some((Token::Symbol(s), _)) if *s == symbol)
+    }
+
+    fn expect_symbol(&mut self, symbol: &str) -> Result<(), ParseError> {
+        match self.take(&format!("'{symbol}'"))? {
+            (Token::Symbol(s), _) if s == symbol => Ok(()),
+            (token, position) => Err(ParseError::new(
+                format!("expected '{symbol}' but found {token}"),
+                position,
+            )),
+        }
+    }
+
+    fn parse_or(&mut self) -> Result<FilterExpr, ParseError> {
+        let mut expr = self.parse_and()?;
+        while self.is_keyword("or") {
+            self.next += 1;
+            expr = FilterExpr::Or(Box::new(expr), Box::new(self.parse_and()?));
+        }
+        Ok(expr)
+    }
+
+    fn parse_and(&mut self) -> Result<FilterExpr, ParseError> {
+        let mut expr = self.parse_not()?;
+        while self.is_keyword("and") {
+            self.next += 1;
+            expr = expr.and(self.parse_not()?);
+        }
+        Ok(expr)
+    }
+
+    fn parse_not(&mut self) -> Result<FilterExpr, ParseError> {
+        if self.is_keyword("not") {
+            self.next += 1;
+            return Ok(self.parse_not()?.negate());
+        }
+        if self.is_symbol("(") {
+            self.next += 1;
+            let expr = self.parse_or()?;
+            self.expect_symbol(")")?;
+            return Ok(expr);
+        }
+        self.parse_condition()
+    }
+
+    fn parse_condition(&mut self) -> Result<FilterExpr, ParseError> {
+        let (field, field_position) = match self.take("a field")? {
+            (Token::Word(w), p) => (w.to_lowercase(), p),
+            (token, p) => {
+                return Err(ParseError::new(
+                    format!("expected a field but found {token}"),
+                    p,
+                ));
+            }
+        };
+        let (operator, operator_position) = match self.take("an operator")? {
+            (Token::Symbol(s), p) if !"()[],".contains(s) => (s, p),
+            (Token::Word(w), p) if w.eq_ignore_ascii_case("in") => ("in", p),
+            (token, p) => {
+                return Err(ParseError::new(
+                    format!("expected an operator after '{field}' but found {token}"),
+                    p,
+                ));
+            }
+        };
+        let wrong_operator = |allowed: &str| {
+            Err(ParseError::new(
+                format!("'{field}' can only be compared with {allowed}"),
+                operator_position,
+            ))
+        };
+
+        match field.as_str() {
+            "size" => {
+                let (value, position) = self.take_value()?;
+                let size = convert_size(&value)
+                    .map(|size| size as u64)
+                    .ok_or_else(|| ParseError::new(format!("invalid size '{value}'"), position))?;
+                match operator {
+                    "=" => Ok(FilterExpr::Size(Some(size), Some(size))),
+                    "!=" => Ok(FilterExpr::Size(Some(size), Some(size)).negate()),
+                    "<" => Ok(FilterExpr::Size(None, Some(size.saturating_sub(1)))),
+                    "<=" => Ok(FilterExpr::Size(None, Some(size))),
+                    ">" => Ok(FilterExpr::Size(Some(size.saturating_add(1)), None)),
+                    ">=" => Ok(FilterExpr::Size(Some(size), None)),
+                    _ => wrong_operator("=, !=, <, <=, > or >="),
+                }
+            }
+            "mtime" | "atime" | "ctime" | "btime" => {
+                let file_time = match field.as_str() {
+                    "mtime" => FileTime::Modified,
+                    "atime" => FileTime::Accessed,
+                    "btime" => FileTime::Created,
+                    _ => FileTime::Changed,
+                };
+                let (value, position) = self.take_value()?;
+                let (older, newer) = match operator {
+                    ">" | ">=" => (Operator::LessThan, Operator::GreaterThan),
+                    "<" | "<=" => (Operator::GreaterThan, Operator::LessThan),
+                    _ => return wrong_operator("<, <=, > or >="),
+                };
+                // Ages are compared so a bigger age is an older file, while a
+                // later date or reference file is a newer one
+                if let Some(age) = parse_duration(&value) {
+                    Ok(FilterExpr::Time(file_time, older, self.now - age))
+                } else if let Some(time) = parse_time_point(&value) {
+                    Ok(FilterExpr::Time(file_time, newer, time))
+                } else {
+                    Err(ParseError::new(
+                        format!("invalid age, date or file '{value}', eg: 90d or 2026-01-01"),
+                        position,
+                    ))
+                }
+            }
+            "path" | "name" | "relpath" => {
+                let (value, position) = self.take_value()?;
+                let regex = match operator {
+                    "=" | "!=" => Regex::new(&format!("^{}$", regex::escape(&value))),
+                    "~" | "!~" => Regex::new(&value),
+                    _ => return wrong_operator("=, !=, ~ or !~"),
+                }
+                .map_err(|e| ParseError::new(format!("invalid regex: {e}"), position))?;
+                let expr = match field.as_str() {
+                    "path" => FilterExpr::PathMatches(regex),
+                    "name" => FilterExpr::NameMatches(regex),
+                    _ => FilterExpr::RelativePathMatches(regex),
+                };
+                if operator.starts_with('!') {
+                    Ok(expr.negate())
+                } else {
+                    Ok(expr)
+                }
+            }
+            "ext" => {
+                let extensions = match operator {
+                    "=" | "!=" => vec![self.take_value()?],
+                    "in" => self.take_list()?,
+                    _ => return wrong_operator("=, != or in"),
+                };
+                let extensions = extensions
+                    .iter()
+                    .map(|(e, _)| e.trim_start_matches('.').to_lowercase())
+                    .collect();
+                let expr = FilterExpr::ExtensionIn(extensions);
+                if operator == "!=" {
+                    Ok(expr.negate())
+                } else {
+                    Ok(expr)
+                }
+            }
+            "user" | "group" => {
+                let names = match operator {
+                    "=" | "!=" => vec![self.take_value()?],
+                    "in" => self.take_list()?,
+                    _ => return wrong_operator("=, != or in"),
+                };
+                let ids = names
+                    .into_iter()
+                    .map(|(name, position)| {
+                        let id = if field == "user" {
+                            parse_user(&name)
+                        } else {
+                            parse_group(&name)
+                        };
+                        id.ok_or_else(|| {
+                            ParseError::new(format!("unknown {field} '{name}'"), position)
+                        })
+                    })
+                    .collect::<Result<_, _>>()?;
+                let expr = if field == "user" {
+                    FilterExpr::UserIn(ids)
+                } else {
+                    FilterExpr::GroupIn(ids)
+                };
+                if operator == "!=" {
+                    Ok(expr.negate())
+                } else {
+                    Ok(expr)
+                }
+            }
+            "perm" => {
+                let (value, position) = self.take_value()?;
+                let mode = parse_mode(&value).ok_or_else(|| {
+                    ParseError::new(format!("invalid permission bits '{value}'"), position)
+                })?;
+                match operator {
+                    "=" => Ok(FilterExpr::Mode(mode)),
+                    "!=" => Ok(FilterExpr::Mode(mode).negate()),
+                    _ => wrong_operator("= or !="),
+                }
+            }
+            _ => Err(ParseError::new(
+                format!(
+                    "unknown field '{field}', expected one of size, mtime, atime, ctime, btime, path, name, relpath, ext, user, group or perm"
+                ),
+                field_position,
+            )),
+        }
+    }
+
+    fn take_value(&mut self) -> Result<(String, usize), ParseError> {
+        match self.take("a value")? {
+            (Token::Word(w), p) | (Token::Quoted(w), p) => Ok((w, p)),
+            (token, p) => Err(ParseError::new(
+                format!("expected a value but found {token}"),
+                p,
+            )),
+        }
+    }
+
+    // [a, b, c]
+    fn take_list(&mut self) -> Result<Vec<(String, usize)>, ParseError> {
+        self.expect_symbol("[")?;
+        let mut values = vec![];
+        loop {
+            if self.is_symbol("]") && values.is_empty() {
+                return Err(ParseError::new(
+                    "expected a value in the list".into(),
+                    self.position(),
+                ));
+            }
+            values.push(self.take_value()?);
+            if self.is_symbol(",") {
+                self.next += 1;
+            } else {
+                self.expect_symbol("]")?;
+                return Ok(values);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    static NOW: i64 = 1_000_000_000;
+
+    fn matches(expr: &str, path: &str, size: u64, age: i64) -> bool {
+        let time = NOW - age;
+        FilterExpr::parse(expr, NOW).unwrap().is_match(&Candidate {
+            path: Path::new(path),
+            root: Path::new("/var"),
+            size,
+            times: (time, time, time, Some(time)),
+            ownership: Some((1000, 100, 0o100644)),
+        })
+    }
+
+    #[test]
+    fn test_filter_expr_example() {
+        let expr = r#"size > 100M and (ext in [log, gz] or mtime > 90d) and not path ~ "/keep/""#;
+        let big = 200 * 1024 * 1024;
+        assert!(matches(expr, "/var/a.log", big, 0));
+        assert!(matches(expr, "/var/a.GZ", big, 0));
+        assert!(matches(expr, "/var/a.bin", big, 100 * DAY_SECONDS));
+        assert!(!matches(expr, "/var/a.bin", big, 10 * DAY_SECONDS));
+        assert!(!matches(expr, "/var/keep/a.log", big, 0));
+        assert!(!matches(expr, "/var/a.log", 1024, 0));
+    }
+
+    #[test]
+    fn test_filter_expr_precedence() {
+        // and binds tighter than or
+        assert!(matches("name = a or name = b and size > 10", "/a", 0, 0));
+        assert!(!matches("(name = a or name = b) and size > 10", "/a", 0, 0));
+        assert!(matches("not not name ~ '^a'", "/x/abc", 0, 0));
+        assert!(matches("size <= 1K AND NOT size < 1024", "/a", 1024, 0));
+        assert!(matches(r#"name == "a \"b\"""#, r#"/a "b""#, 0, 0));
+        assert!(matches(r#"path ~ "\.txt$""#, "/dir/a.txt", 0, 0));
+        assert!(matches("mtime > 2001-01-01", "/a", 0, 0));
+        assert!(!matches("mtime < '2001-01-01 12:00'", "/a", 0, 0));
+        assert!(!matches(r#"path ~ "\.txt$""#, "/dir/atxt", 0, 0));
+    }
+
+    #[test]
+    fn test_filter_expr_unknown_birth_time() {
+        let expr = FilterExpr::parse("btime > 1d or not btime > 1d", NOW).unwrap();
+        let mut candidate = Candidate {
+            path: Path::new("/a"),
+            root: Path::new("/"),
+            size: 0,
+            times: (NOW, NOW, NOW, Some(NOW)),
+            ownership: None,
+        };
+        assert!(expr.is_match(&candidate));
+        // Neither side can hold without a birth time, but the negation can
+        candidate.times.3 = None;
+        assert!(
+            !FilterExpr::parse("btime > 1d", NOW)
+                .unwrap()
+                .is_match(&candidate)
+        );
+        assert!(
+            !FilterExpr::parse("btime < 1d", NOW)
+                .unwrap()
+                .is_match(&candidate)
+        );
+    }
+
+    #[test]
+    fn test_filter_expr_applies_to_dirs() {
+        let applies_to_dirs = |expr| FilterExpr::parse(expr, NOW).unwrap().applies_to_dirs();
+        assert!(applies_to_dirs("name = a and mtime < 1d"));
+        assert!(!applies_to_dirs("size > 1M"));
+        assert!(!applies_to_dirs("name = a or not size < 1K"));
+        assert!(!applies_to_dirs("user = 0"));
+        assert!(!applies_to_dirs("mtime < 1d and perm = 644"));
+    }
+
+    #[test]
+    fn test_filter_expr_path_parts() {
+        assert!(matches("relpath ~ '^build/'", "/var/build/a.o", 0, 0));
+        assert!(!matches("path ~ '^build/'", "/var/build/a.o", 0, 0));
+        assert!(matches("name ~ '^a'", "/var/build/a.o", 0, 0));
+        assert!(matches("relpath = log", "/var/log", 0, 0));
+    }
+
+    #[cfg(target_family = "unix")]
+    #[test]
+    fn test_regex_matches_non_utf8_names() {
+        use std::ffi::OsStr;
+        use std::os::unix::ffi::OsStrExt;
+
+        let path = Path::new(OsStr::from_bytes(b"/var/caf\xe9.JPG"));
+        let candidate = Candidate {
+            path,
+            root: Path::new("/var"),
+            size: 0,
+            times: (0, 0, 0, None),
+            ownership: None,
+        };
+        let regex = regex::bytes::RegexBuilder::new(r"(?-u)^caf\xe9\.jpg$")
+            .case_insensitive(true)
+            .build()
+            .unwrap();
+        let expr = FilterExpr::any_matches(vec![regex], MatchOn::Name).unwrap();
+        assert!(expr.is_match(&candidate));
+        let expr = FilterExpr::parse("name ~ '\\.JPG$'", NOW).unwrap();
+        assert!(expr.is_match(&candidate));
+    }
+
+    #[test]
+    fn test_filter_expr_ownership() {
+        assert!(matches("user = 1000 and group in [0, 100]", "/a", 0, 0));
+        assert!(matches("user != 0 and perm = 644", "/a", 0, 0));
+        assert!(matches("perm = /go+r and not perm = /o+w", "/a", 0, 0));
+        assert!(!matches("perm = -u+s", "/a", 0, 0));
+        let error = |expr| FilterExpr::parse(expr, NOW).unwrap_err();
+        assert_eq!(error("user in [0, no-such-user-for-dust]").position, 12);
+        assert_eq!(error("perm = rwx").message, "invalid permission bits 'rwx'");
+        assert_eq!(error("perm > 644").position, 5);
+    }
+
+    #[test]
+    fn test_filter_expr_parse_errors() {
+        let error = |expr| FilterExpr::parse(expr, NOW).unwrap_err();
+        assert_eq!(
+            error("size > 10M and"),
+            ParseError::new("expected a field but the expression ended".into(), 14)
+        );
+        assert_eq!(error("colour = red").position, 0);
+        assert_eq!(error("size > big").message, "invalid size 'big'");
+        assert_eq!(error("size > 1.5K").message, "invalid size '1.5K'");
+        assert_eq!(error("mtime = 5d").position, 6);
+        assert_eq!(error("path ~ \"(\"").position, 7);
+        assert_eq!(
+            error("(size > 1").message,
+            "expected ')' but the expression ended"
+        );
+        assert_eq!(error("ext in []").position, 8);
+        assert_eq!(error("name = 'a").message, "unterminated quoted string");
+        assert_eq!(error("size > 1 size").position, 9);
+        assert_eq!(
+            error("size > big").describe("size > big"),
+            "invalid size 'big'\n  size > big\n         ^"
+        );
+    }
+}
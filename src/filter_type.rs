@@ -9,6 +9,12 @@ use std::path::PathBuf;
 struct ExtensionNode<'a> {
     size: u64,
     extension: Option<&'a OsStr>,
+    disk_size: u64,
+    apparent_size: u64,
+    file_count: u64,
+    newest_mtime: i64,
+    oldest_mtime: i64,
+    newest_btime: Option<i64>,
 }
 
 pub fn get_all_file_types(
@@ -24,10 +30,8 @@ pub fn get_all_file_types(
             by_filetime,
         );
 
-        let mut extension_cumulative_sizes: Vec<ExtensionNode<'_>> = extension_cumulative_sizes
-            .iter()
-            .map(|(&extension, &size)| ExtensionNode { extension, size })
-            .collect();
+        let mut extension_cumulative_sizes: Vec<ExtensionNode<'_>> =
+            extension_cumulative_sizes.into_values().collect();
 
         extension_cumulative_sizes.sort_by(|lhs, rhs| lhs.cmp(rhs).reverse());
 
@@ -48,20 +52,47 @@ pub fn get_all_file_types(
             ),
             size: node.size,
             children: vec![],
+            disk_size: node.disk_size,
+            apparent_size: node.apparent_size,
+            file_count: node.file_count,
+            dir_count: 0,
+            newest_mtime: node.newest_mtime,
+            oldest_mtime: node.oldest_mtime,
+            newest_btime: node.newest_btime,
         })
         .collect();
 
     // ...then, aggregate the remaining nodes (if any) into a single  "(others)" node
     if ext_nodes_iter.len() > 0 {
+        let remaining: Vec<_> = ext_nodes_iter.collect();
         let actual_size = if by_filetime.is_some() {
-            ext_nodes_iter.map(|node| node.size).max().unwrap_or(0)
+            remaining.iter().map(|node| node.size).max().unwrap_or(0)
         } else {
-            ext_nodes_iter.map(|node| node.size).sum()
+            remaining.iter().map(|node| node.size).sum()
         };
         displayed.push(DisplayNode {
             name: PathBuf::from("(others)"),
             size: actual_size,
             children: vec![],
+            disk_size: remaining.iter().map(|node| node.disk_size).sum(),
+            apparent_size: remaining.iter().map(|node| node.apparent_size).sum(),
+            file_count: remaining.iter().map(|node| node.file_count).sum(),
+            dir_count: 0,
+            newest_mtime: remaining
+                .iter()
+                .map(|node| node.newest_mtime)
+                .max()
+                .unwrap_or(0),
+            oldest_mtime: remaining
+                .iter()
+                .map(|node| node.oldest_mtime)
+                .min()
+                .unwrap_or(i64::MAX),
+            newest_btime: remaining
+                .iter()
+                .map(|node| node.newest_btime)
+                .max()
+                .flatten(),
         });
         // '(others)' is the sum of the remaining nodes so it can be bigger than
         // the nodes above it: re-sort so the tree stays in size order.
@@ -77,25 +108,59 @@ pub fn get_all_file_types(
     DisplayNode {
         name: PathBuf::from("(total)"),
         size: actual_size,
+        disk_size: displayed.iter().map(|node| node.disk_size).sum(),
+        apparent_size: displayed.iter().map(|node| node.apparent_size).sum(),
+        file_count: displayed.iter().map(|node| node.file_count).sum(),
+        dir_count: 0,
+        newest_mtime: displayed
+            .iter()
+            .map(|node| node.newest_mtime)
+            .max()
+            .unwrap_or(0),
+        oldest_mtime: displayed
+            .iter()
+            .map(|node| node.oldest_mtime)
+            .min()
+            .unwrap_or(i64::MAX),
+        newest_btime: displayed
+            .iter()
+            .map(|node| node.newest_btime)
+            .max()
+            .flatten(),
         children: displayed,
     }
 }
 
 fn build_by_all_file_types<'a>(
     top_level_nodes: &'a [Node],
-    counter: &mut HashMap<Option<&'a OsStr>, u64>,
+    counter: &mut HashMap<Option<&'a OsStr>, ExtensionNode<'a>>,
     by_filetime: &Option<FileTime>,
 ) {
     for node in top_level_nodes {
         if node.name.is_file() {
-            let ext = node.name.extension();
-            let cumulative_size = counter.entry(ext).or_default();
+            let extension = node.name.extension();
+            let totals = counter.entry(extension).or_insert(ExtensionNode {
+                size: 0,
+                extension,
+                disk_size: 0,
+                apparent_size: 0,
+                file_count: 0,
+                newest_mtime: 0,
+                oldest_mtime: i64::MAX,
+                newest_btime: None,
+            });
             if by_filetime.is_some() {
                 // 'size' is a timestamp, summing them is meaningless
-                *cumulative_size = (*cumulative_size).max(node.size);
+                totals.size = totals.size.max(node.size);
             } else {
-                *cumulative_size += node.size;
+                totals.size += node.size;
             }
+            totals.disk_size += node.disk_size;
+            totals.apparent_size += node.apparent_size;
+            totals.file_count += node.file_count;
+            totals.newest_mtime = totals.newest_mtime.max(node.newest_mtime);
+            totals.oldest_mtime = totals.oldest_mtime.min(node.oldest_mtime);
+            totals.newest_btime = totals.newest_btime.max(node.newest_btime);
         }
         build_by_all_file_types(&node.children, counter, by_filetime)
     }
@@ -109,6 +174,13 @@ mod tests {
         Node {
             name: PathBuf::from(name),
             size,
+            disk_size: size,
+            apparent_size: size,
+            file_count: 1,
+            dir_count: 0,
+            newest_mtime: 0,
+            oldest_mtime: i64::MAX,
+            newest_btime: None,
             children: vec![],
             inode_device: None,
             depth: 1,
@@ -0,0 +1,155 @@
+use serde::Serialize;
+use std::fs;
+use std::path::Path;
+use std::path::PathBuf;
+use thousands::Separable;
+
+use crate::display::get_printable_name;
+use crate::display::human_readable_number;
+use crate::node::Node;
+
+// The directories from the scanned path down to the focused one (included), with
+// their share of everything scanned
+#[derive(Debug, Serialize)]
+pub struct Ancestor {
+    pub name: PathBuf,
+    pub size: u64,
+    // None when the sizes are times (-m), which do not add up
+    pub percent: Option<f64>,
+}
+
+// Takes the node at `focus` out of the scanned tree, as if it had been scanned on
+// its own, so -d, -n and the percentages all count from it
+pub fn focus_on(
+    top_level_nodes: Vec<Node>,
+    focus: &Path,
+    by_filetime: bool,
+) -> Option<(Node, Vec<Ancestor>)> {
+    let total = if by_filetime {
+        None
+    } else {
+        Some(top_level_nodes.iter().map(|node| node.size).sum::<u64>())
+    };
+    let (mut node, rest) = top_level_nodes
+        .into_iter()
+        .find_map(|node| get_relative(&node.name, focus).map(|rest| (node, rest)))?;
+
+    let mut ancestors = vec![];
+    for part in rest.components() {
+        let index = node
+            .children
+            .iter()
+            .position(|c| c.name.file_name() == Some(part.as_os_str()))?;
+        ancestors.push(build_ancestor(&node, total));
+        node = node.children.swap_remove(index);
+    }
+    ancestors.push(build_ancestor(&node, total));
+
+    rebase_depth(&mut node, 0);
+    Some((node, ancestors))
+}
+
+fn build_ancestor(node: &Node, total: Option<u64>) -> Ancestor {
+    Ancestor {
+        name: node.name.clone(),
+        size: node.size,
+        percent: total.map(|total| {
+            if total == 0 {
+                0.0
+            } else {
+                node.size as f64 * 100.0 / total as f64
+            }
+        }),
+    }
+}
+
+// The path of `focus` below `top`, comparing canonical paths when they are written
+// differently (eg: '.' and 'src')
+fn get_relative(top: &Path, focus: &Path) -> Option<PathBuf> {
+    if let Ok(rest) = focus.strip_prefix(top) {
+        return Some(rest.to_path_buf());
+    }
+    let top = fs::canonicalize(top).ok()?;
+    let focus = fs::canonicalize(focus).ok()?;
+    focus.strip_prefix(top).ok().map(Path::to_path_buf)
+}
+
+fn rebase_depth(node: &mut Node, depth: usize) {
+    node.depth = depth;
+    for child in node.children.iter_mut() {
+        rebase_depth(child, depth + 1);
+    }
+}
+
+pub fn draw_breadcrumb(ancestors: &[Ancestor], output_format: &str, by_filecount: bool) {
+    println!(
+        "{}",
+        format_breadcrumb(ancestors, output_format, by_filecount)
+    );
+}
+
+fn format_breadcrumb(ancestors: &[Ancestor], output_format: &str, by_filecount: bool) -> String {
+    ancestors
+        .iter()
+        .enumerate()
+        .map(|(i, ancestor)| {
+            // The scanned path in full, below it only the names
+            let name = get_printable_name(&ancestor.name, i > 0);
+            match ancestor.percent {
+                Some(percent) => {
+                    let size = if by_filecount {
+                        ancestor.size.separate_with_commas()
+                    } else {
+                        human_readable_number(ancestor.size, output_format)
+                    };
+                    format!("{name} {size} {percent:.0}%")
+                }
+                None => name,
+            }
+        })
+        .collect::<Vec<_>>()
+        .join(" > ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn build(name: &str, size: u64, depth: usize, children: Vec<Node>) -> Node {
+        Node {
+            name: PathBuf::from(name),
+            size,
+            disk_size: size,
+            apparent_size: size,
+            file_count: 1,
+            dir_count: 0,
+            newest_mtime: 0,
+            oldest_mtime: i64::MAX,
+            newest_btime: None,
+            children,
+            inode_device: None,
+            depth,
+        }
+    }
+
+    #[test]
+    fn test_focus_on() {
+        let docker = build("/var/lib/docker", 1024, 2, vec![]);
+        let lib = build("/var/lib", 3072, 1, vec![docker]);
+        let log = build("/var/log", 1024, 1, vec![]);
+        let var = build("/var", 4096, 0, vec![lib, log]);
+
+        let (node, ancestors) =
+            focus_on(vec![var.clone()], Path::new("/var/lib/docker"), false).unwrap();
+        assert_eq!(node.name, PathBuf::from("/var/lib/docker"));
+        assert_eq!(node.depth, 0);
+        assert_eq!(
+            format_breadcrumb(&ancestors, "", false),
+            "/var 4.0Ki 100% > lib 3.0Ki 75% > docker 1.0Ki 25%"
+        );
+
+        let (node, _) = focus_on(vec![var.clone()], Path::new("/var/lib"), false).unwrap();
+        assert_eq!(node.children[0].depth, 1);
+        assert!(focus_on(vec![var], Path::new("/var/cache"), false).is_none());
+    }
+}
@@ -0,0 +1,96 @@
+use globset::GlobBuilder;
+use globset::GlobSet;
+use globset::GlobSetBuilder;
+use std::path::Path;
+
+// Globs excluding files and directories, in the style of rsync and tar: a glob
+// matches the last components of a path relative to the scanned directory, so
+// `*.o` and `node_modules` match at any depth and `build/*/cache` matches a
+// cache directory two levels below any build directory. A leading slash
+// anchors the glob to the scanned directory and a trailing slash only matches
+// directories.
+#[derive(Default)]
+pub struct ExcludeGlobs {
+    any: GlobSet,
+    dirs_only: GlobSet,
+}
+
+impl ExcludeGlobs {
+    pub fn new<S: AsRef<str>>(patterns: &[S]) -> Result<ExcludeGlobs, globset::Error> {
+        let mut any = GlobSetBuilder::new();
+        let mut dirs_only = GlobSetBuilder::new();
+        for pattern in patterns {
+            let pattern = pattern.as_ref();
+            let (pattern, builder) = match pattern.strip_suffix('/') {
+                Some(p) => (p, &mut dirs_only),
+                None => (pattern, &mut any),
+            };
+            let pattern = match pattern.strip_prefix('/') {
+                Some(anchored) => anchored.to_owned(),
+                None => format!("**/{}", pattern.strip_prefix("**/").unwrap_or(pattern)),
+            };
+            let glob = GlobBuilder::new(&pattern).literal_separator(true).build()?;
+            builder.add(glob);
+        }
+        Ok(ExcludeGlobs {
+            any: any.build()?,
+            dirs_only: dirs_only.build()?,
+        })
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.any.is_empty() && self.dirs_only.is_empty()
+    }
+
+    pub fn is_match(&self, path: &Path, is_dir: bool) -> bool {
+        self.any.is_match(path) || is_dir && self.dirs_only.is_match(path)
+    }
+}
+
+// One glob per line, blank lines and lines starting with '#' are skipped
+pub fn parse_exclude_file(content: &str) -> Vec<String> {
+    content
+        .lines()
+        .map(|l| l.trim_end_matches('\r'))
+        .filter(|l| !l.trim().is_empty() && !l.starts_with('#'))
+        .map(str::to_owned)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_exclude_globs_match_path_components() {
+        let globs =
+            ExcludeGlobs::new(&["**/node_modules", "*.o", "build/*/cache", "tmp/"]).unwrap();
+        assert!(globs.is_match(Path::new("./web/node_modules"), true));
+        assert!(globs.is_match(Path::new("node_modules"), true));
+        assert!(globs.is_match(Path::new("/src/lib/main.o"), false));
+        assert!(globs.is_match(Path::new("proj/build/debug/cache"), true));
+        assert!(!globs.is_match(Path::new("proj/build/a/b/cache"), true));
+        assert!(!globs.is_match(Path::new("proj/build/debug/cache/x"), false));
+        assert!(!globs.is_match(Path::new("src/main.rs"), false));
+        assert!(!globs.is_match(Path::new("my_node_modules"), true));
+        assert!(globs.is_match(Path::new("a/tmp"), true));
+        assert!(!globs.is_match(Path::new("a/tmp"), false));
+    }
+
+    #[test]
+    fn test_exclude_globs_anchored() {
+        let globs = ExcludeGlobs::new(&["/target", "/docs/*.md", "/out/"]).unwrap();
+        assert!(globs.is_match(Path::new("target"), true));
+        assert!(!globs.is_match(Path::new("sub/target"), true));
+        assert!(globs.is_match(Path::new("docs/intro.md"), false));
+        assert!(!globs.is_match(Path::new("sub/docs/intro.md"), false));
+        assert!(globs.is_match(Path::new("out"), true));
+        assert!(!globs.is_match(Path::new("out"), false));
+    }
+
+    #[test]
+    fn test_parse_exclude_file() {
+        let content = "# build output\n*.o\n\ntarget/\r\n";
+        assert_eq!(parse_exclude_file(content), vec!["*.o", "target/"]);
+    }
+}
@@ -0,0 +1,247 @@
+use ignore::Match;
+use ignore::gitignore::Gitignore;
+use ignore::gitignore::GitignoreBuilder;
+use ignore::gitignore::gitconfig_excludes_path;
+use std::path::Path;
+use std::path::PathBuf;
+use std::sync::Arc;
+
+// Later files take precedence: .git/info/exclude, then .gitignore, then .ignore
+static GIT_IGNORE_FILES: [&str; 3] = [".git/info/exclude", ".gitignore", ".ignore"];
+static DUST_IGNORE_FILES: [&str; 1] = [".dustignore"];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GitIgnoreMode {
+    // Leave out what git ignores
+    Skip,
+    // Only keep what git ignores
+    OnlyIgnored,
+}
+
+// The ignore rules in force in a directory: those of the directory itself and
+// of each of its parents, innermost first. Git rules stop at the repository
+// root and end with the global git excludes file, .dustignore rules start at
+// the scanned directory so files above it do not change its totals.
+// Rules are matched against absolute paths as the walk paths may be relative.
+#[derive(Clone)]
+pub struct IgnoreRules {
+    // The walked top level path and its absolute form
+    top: Arc<(PathBuf, PathBuf)>,
+    git: Option<Vec<Arc<Gitignore>>>,
+    dust: Option<Vec<Arc<Gitignore>>>,
+    // Set beneath a directory ignored by git
+    pub in_git_ignored: bool,
+}
+
+impl IgnoreRules {
+    // Rules for a top level path, its own ignore files are added by `descend`
+    // like those of every other directory
+    pub fn for_top_level(dir: &Path, use_git: bool, use_dust: bool) -> IgnoreRules {
+        let absolute = std::path::absolute(dir).unwrap_or_else(|_| dir.to_path_buf());
+        let mut rules = IgnoreRules {
+            top: Arc::new((dir.to_path_buf(), absolute.clone())),
+            git: use_git.then(Vec::new),
+            dust: use_dust.then(Vec::new),
+            in_git_ignored: false,
+        };
+
+        if let Some(git) = &mut rules.git {
+            let repo_root = absolute
+                .ancestors()
+                .find(|a| a.join(".git").exists())
+                .unwrap_or(&absolute);
+            if let Some(global) = gitconfig_excludes_path().filter(|p| p.is_file()) {
+                let mut builder = GitignoreBuilder::new(repo_root);
+                builder.add(global);
+                if let Ok(gitignore) = builder.build() {
+                    git.push(Arc::new(gitignore));
+                }
+            }
+            git.extend(get_parent_matchers(&absolute, repo_root, &GIT_IGNORE_FILES));
+        }
+        rules
+    }
+
+    // Rules for a directory below `self`, only copied if the directory has its own.
+    // `has_entry` tells if a name is in the directory listing, only the ignore
+    // files it holds are read.
+    pub fn descend(
+        self: &Arc<Self>,
+        dir: &Path,
+        has_entry: impl Fn(&str) -> bool,
+    ) -> Arc<IgnoreRules> {
+        let absolute = self.to_absolute(dir);
+        let git = self.git.as_ref().and_then(|_| {
+            let files = get_listed_files(&GIT_IGNORE_FILES, &has_entry);
+            build_matcher(&absolute, &files)
+        });
+        let dust = self.dust.as_ref().and_then(|_| {
+            let files = get_listed_files(&DUST_IGNORE_FILES, &has_entry);
+            build_matcher(&absolute, &files)
+        });
+        if git.is_none() && dust.is_none() {
+            return self.clone();
+        }
+
+        let mut rules = IgnoreRules::clone(self);
+        if let (Some(matchers), Some(gitignore)) = (&mut rules.git, git) {
+            matchers.push(Arc::new(gitignore));
+        }
+        if let (Some(matchers), Some(dustignore)) = (&mut rules.dust, dust) {
+            matchers.push(Arc::new(dustignore));
+        }
+        Arc::new(rules)
+    }
+
+    pub fn inside_git_ignored(self: &Arc<Self>) -> Arc<IgnoreRules> {
+        Arc::new(IgnoreRules {
+            in_git_ignored: true,
+            ..IgnoreRules::clone(self)
+        })
+    }
+
+    pub fn is_git_ignored(&self, path: &Path, is_dir: bool) -> bool {
+        self.git
+            .as_ref()
+            .is_some_and(|m| is_ignored(m, &self.to_absolute(path), is_dir))
+    }
+
+    pub fn is_dust_ignored(&self, path: &Path, is_dir: bool) -> bool {
+        self.dust
+            .as_ref()
+            .is_some_and(|m| is_ignored(m, &self.to_absolute(path), is_dir))
+    }
+
+    fn to_absolute(&self, path: &Path) -> PathBuf {
+        let (top, absolute_top) = &*self.top;
+        match path.strip_prefix(top) {
+            Ok(rest) if rest.as_os_str().is_empty() => absolute_top.clone(),
+            Ok(rest) => absolute_top.join(rest),
+            Err(_) => path.to_path_buf(),
+        }
+    }
+}
+
+fn is_ignored(matchers: &[Arc<Gitignore>], absolute: &Path, is_dir: bool) -> bool {
+    for matcher in matchers.iter().rev() {
+        match matcher.matched(absolute, is_dir) {
+            Match::Ignore(_) => return true,
+            Match::Whitelist(_) => return false,
+            Match::None => {}
+        }
+    }
+    false
+}
+
+// Matchers of the parents of `dir` up to and including `stop_at`, outermost first
+fn get_parent_matchers(dir: &Path, stop_at: &Path, files: &[&str]) -> Vec<Arc<Gitignore>> {
+    let mut parents: Vec<_> = dir
+        .ancestors()
+        .skip(1)
+        .take_while(|a| a.starts_with(stop_at))
+        .collect();
+    parents.reverse();
+    parents
+        .into_iter()
+        .filter_map(|p| build_matcher(p, files))
+        .map(Arc::new)
+        .collect()
+}
+
+// The ignore files whose first component, eg: '.git', is in the listing
+fn get_listed_files<'a>(files: &[&'a str], has_entry: impl Fn(&str) -> bool) -> Vec<&'a str> {
+    files
+        .iter()
+        .filter(|f| has_entry(f.split('/').next().unwrap_or(f)))
+        .copied()
+        .collect()
+}
+
+fn build_matcher(dir: &Path, files: &[&str]) -> Option<Gitignore> {
+    if files.is_empty() {
+        return None;
+    }
+    let files: Vec<_> = files
+        .iter()
+        .map(|f| dir.join(f))
+        .filter(|f| f.is_file())
+        .collect();
+    if files.is_empty() {
+        return None;
+    }
+
+    let mut builder = GitignoreBuilder::new(dir);
+    for file in files {
+        // A bad line only loses that pattern, as with git
+        builder.add(file);
+    }
+    builder.build().ok().filter(|g| !g.is_empty())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    fn descend_into(rules: &IgnoreRules, dir: &Path) -> Arc<IgnoreRules> {
+        Arc::new(rules.clone()).descend(dir, |name| dir.join(name).exists())
+    }
+
+    #[test]
+    fn test_nested_ignore_files() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        fs::create_dir_all(root.join(".git/info")).unwrap();
+        fs::create_dir_all(root.join("sub")).unwrap();
+        fs::write(root.join(".gitignore"), "*.log\ntarget/\n").unwrap();
+        fs::write(root.join(".git/info/exclude"), "secret\n").unwrap();
+        fs::write(root.join("sub/.gitignore"), "!keep.log\n").unwrap();
+        fs::write(root.join("sub/.ignore"), "*.tmp\n").unwrap();
+
+        let top = descend_into(&IgnoreRules::for_top_level(root, true, false), root);
+        assert!(top.is_git_ignored(&root.join("a.log"), false));
+        assert!(top.is_git_ignored(&root.join("target"), true));
+        assert!(!top.is_git_ignored(&root.join("target"), false));
+        assert!(top.is_git_ignored(&root.join("secret"), false));
+        assert!(!top.is_git_ignored(&root.join("sub"), true));
+
+        let sub = descend_into(&top, &root.join("sub"));
+        assert!(sub.is_git_ignored(&root.join("sub/a.log"), false));
+        assert!(!sub.is_git_ignored(&root.join("sub/keep.log"), false));
+        assert!(sub.is_git_ignored(&root.join("sub/a.tmp"), false));
+        assert!(!top.is_git_ignored(&root.join("sub/a.tmp"), false));
+
+        // Scanning from inside the repository still uses the parent rules
+        let sub = descend_into(
+            &IgnoreRules::for_top_level(&root.join("sub"), true, false),
+            &root.join("sub"),
+        );
+        assert!(sub.is_git_ignored(&root.join("sub/a.log"), false));
+        assert!(sub.is_git_ignored(&root.join("sub/secret"), false));
+    }
+
+    #[test]
+    fn test_dustignore_is_inherited() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        fs::create_dir_all(root.join("project/cache")).unwrap();
+        fs::write(root.join(".dustignore"), "*.iso\n").unwrap();
+        fs::write(root.join("project/.dustignore"), "cache/\n!keep.iso\n").unwrap();
+
+        let rules = descend_into(&IgnoreRules::for_top_level(root, false, true), root);
+        assert!(!rules.is_dust_ignored(&root.join("cache"), true));
+        assert!(rules.is_dust_ignored(&root.join("disk.iso"), false));
+
+        let project = root.join("project");
+        let rules = descend_into(&rules, &project);
+        assert!(rules.is_dust_ignored(&project.join("cache"), true));
+        assert!(rules.is_dust_ignored(&project.join("disk.iso"), false));
+        assert!(!rules.is_dust_ignored(&project.join("keep.iso"), false));
+        assert!(!rules.is_git_ignored(&project.join("disk.iso"), false));
+
+        // Files above the scanned directory are not read
+        let rules = descend_into(&IgnoreRules::for_top_level(&project, false, true), &project);
+        assert!(!rules.is_dust_ignored(&project.join("disk.iso"), false));
+        assert!(rules.is_dust_ignored(&project.join("cache"), true));
+    }
+}
@@ -1,25 +1,53 @@
 mod cli;
 mod config;
+mod deleted_files;
 mod dir_walker;
 mod display;
 mod display_node;
+mod excluded;
+mod filesystem;
 mod filter;
+mod filter_expr;
 mod filter_type;
+mod focus;
+mod glob_exclude;
+mod ignore_rules;
 mod node;
+mod owner_filter;
 mod platform;
 mod progress;
+mod reconcile;
+mod theme;
+mod time_filter;
 mod utils;
 
 use crate::cli::Cli;
+use crate::cli::Column;
 use crate::config::Config;
 use crate::display_node::DisplayNode;
+use crate::display_node::add_filesystem_percents;
+use crate::display_node::add_fs_types;
+use crate::display_node::add_percents;
 use crate::progress::RuntimeErrors;
+use chrono::Local;
 use clap::Parser;
+use dir_walker::Operator;
+use dir_walker::SkippedData;
 use dir_walker::WalkData;
 use display::InitialDisplayData;
+use excluded::ExcludedReport;
+use excluded::draw_excluded_report;
+use excluded::get_excluded_report;
 use filter::AggregateData;
+use focus::Ancestor;
+use focus::draw_breadcrumb;
+use focus::focus_on;
 use progress::PIndicator;
+use reconcile::Reconciliation;
+use reconcile::draw_reconciliation;
+use reconcile::reconcile;
 use regex::Error;
+use std::collections::HashMap;
 use std::collections::HashSet;
 use std::env;
 use std::fs::{read, read_to_string};
@@ -31,23 +59,49 @@ use std::sync::Arc;
 use std::sync::Mutex;
 use sysinfo::System;
 use utils::canonicalize_absolute_path;
+use utils::normalize_path;
 
+use self::display::draw_filesystem_info;
+use self::display::draw_flat;
 use self::display::draw_it;
 use config::get_config;
+use deleted_files::get_deleted_open_files;
 use dir_walker::walk_it;
 use display_node::OUTPUT_TYPE;
+use filesystem::FilesystemInfo;
+use filesystem::get_excluded_mounts;
+use filesystem::get_filesystem_info;
+use filesystem::get_mount_point_types;
+use filesystem::read_mount_table;
 use filter::get_biggest;
+use filter::get_flat;
+use filter_expr::FilterExpr;
+use filter_expr::FilterKind;
 use filter_type::get_all_file_types;
-use regex::Regex;
+use glob_exclude::ExcludeGlobs;
+use glob_exclude::parse_exclude_file;
+use node::FileTime;
+use regex::bytes::Regex;
+use regex::bytes::RegexBuilder;
 use std::cmp::max;
 use std::path::PathBuf;
 use terminal_size::{Height, Width, terminal_size};
 use utils::get_filesystem_devices;
+use utils::get_ignored_paths;
 use utils::simplify_dir_names;
 
 static DEFAULT_NUMBER_OF_LINES: usize = 30;
 static DEFAULT_TERMINAL_WIDTH: usize = 80;
 
+// Extra information printed around the tree, added as fields to the json output
+struct Reports {
+    filesystems: Vec<FilesystemInfo>,
+    mount_point_types: HashMap<PathBuf, String>,
+    reconciliation: Option<Reconciliation>,
+    excluded: Option<ExcludedReport>,
+    ancestors: Option<Vec<Ancestor>>,
+}
+
 fn should_init_color(no_color: bool, force_color: bool) -> bool {
     if force_color {
         return true;
@@ -101,12 +155,12 @@ fn get_width_of_terminal() -> usize {
         .unwrap_or(DEFAULT_TERMINAL_WIDTH)
 }
 
-fn get_regex_value(maybe_value: Option<&Vec<String>>) -> Vec<Regex> {
+fn get_regex_value(maybe_value: Option<&Vec<String>>, ignore_case: bool) -> Vec<Regex> {
     maybe_value
         .unwrap_or(&Vec::new())
         .iter()
         .map(|reg| {
-            Regex::new(reg).unwrap_or_else(|err| {
+            build_regex(reg, ignore_case).unwrap_or_else(|err| {
                 eprintln!("Ignoring bad value for regex {err:?}");
                 process::exit(1)
             })
@@ -114,6 +168,21 @@ fn get_regex_value(maybe_value: Option<&Vec<String>>) -> Vec<Regex> {
         .collect()
 }
 
+fn build_regex(regex: &str, ignore_case: bool) -> Result<Regex, Error> {
+    RegexBuilder::new(regex)
+        .case_insensitive(ignore_case)
+        .build()
+}
+
+fn get_where_value(maybe_value: Option<&String>) -> Option<FilterExpr> {
+    maybe_value.map(|expr| {
+        FilterExpr::parse(expr, Local::now().timestamp()).unwrap_or_else(|err| {
+            eprintln!("Invalid --where expression: {}", err.describe(expr));
+            process::exit(1)
+        })
+    })
+}
+
 fn main() {
     let options = Cli::parse();
     let config = get_config(options.config.as_ref());
@@ -144,8 +213,9 @@ fn main() {
 
     let summarize_file_types = options.file_types;
 
-    let filter_regexs = get_regex_value(options.filter.as_ref());
-    let invert_filter_regexs = get_regex_value(options.invert_filter.as_ref());
+    let ignore_case = config.get_ignore_case(&options);
+    let filter_regexs = get_regex_value(options.filter.as_ref(), ignore_case);
+    let invert_filter_regexs = get_regex_value(options.invert_filter.as_ref(), ignore_case);
 
     let terminal_width: usize = match options.terminal_width {
         Some(val) => val,
@@ -187,7 +257,7 @@ fn main() {
         Some(ref val) => read_to_string(val)
             .unwrap()
             .lines()
-            .map(Regex::new)
+            .map(|line| build_regex(line, ignore_case))
             .collect::<Vec<Result<Regex, Error>>>(),
         None => vec![],
     };
@@ -196,6 +266,21 @@ fn main() {
         .filter_map(|x| x.ok())
         .collect::<Vec<Regex>>();
 
+    let mut exclude_globs = config.get_exclude(&options);
+    if let Some(ref path) = options.exclude_from {
+        match read_to_string(path) {
+            Ok(content) => exclude_globs.extend(parse_exclude_file(&content)),
+            Err(err) => {
+                eprintln!("Could not read exclude file {path}: {err}");
+                process::exit(1)
+            }
+        }
+    }
+    let exclude_globs = ExcludeGlobs::new(&exclude_globs).unwrap_or_else(|err| {
+        eprintln!("Bad value for glob {err}");
+        process::exit(1)
+    });
+
     let invert_filter_regexs = invert_filter_regexs
         .into_iter()
         .chain(ignore_from_file)
@@ -206,18 +291,57 @@ fn main() {
     let limit_filesystem = config.get_limit_filesystem(&options);
     let follow_links = options.dereference_links;
 
+    let include_fstype = config.get_include_fstype(&options);
+    let exclude_fstype = config.get_exclude_fstype(&options);
+    if limit_filesystem && !include_fstype.is_empty() {
+        eprintln!("include-fstype can not be used with -x, which keeps to the filesystems given");
+        process::exit(1);
+    }
+    // Filesystem types are only looked at when asked for
+    let mount_table = if !include_fstype.is_empty()
+        || !exclude_fstype.is_empty()
+        || config.get_filesystem_info(&options)
+        || config.get_filesystem_percent(&options)
+    {
+        read_mount_table()
+    } else {
+        vec![]
+    };
+
     let allowed_filesystems = if limit_filesystem {
         get_filesystem_devices(&target_dirs, follow_links)
     } else {
         Default::default()
     };
 
+    let show_deleted_open_files = config.get_deleted_open_files(&options);
+    let show_reconciliation = config.get_reconcile(&options);
+    let show_excluded = config.get_show_excluded(&options);
+
+    let deleted_files_devices = if show_deleted_open_files || show_reconciliation {
+        get_filesystem_devices(&target_dirs, follow_links)
+    } else {
+        Default::default()
+    };
+
     let simplified_dirs = simplify_dir_names(&target_dirs);
+    let walked_dirs: Vec<_> = simplified_dirs.iter().collect();
+    let excluded_mounts =
+        get_excluded_mounts(&mount_table, &walked_dirs, &include_fstype, &exclude_fstype);
+    let mount_point_types = get_mount_point_types(&mount_table, &walked_dirs);
+
+    let filesystems = if config.get_filesystem_info(&options)
+        || config.get_filesystem_percent(&options)
+        || show_reconciliation
+    {
+        let mut roots: Vec<_> = simplified_dirs.iter().collect();
+        roots.sort();
+        get_filesystem_info(&roots)
+    } else {
+        vec![]
+    };
 
-    let ignored_full_path: HashSet<PathBuf> = ignore_directories
-        .into_iter()
-        .flat_map(|x| simplified_dirs.iter().map(move |d| d.join(&x)))
-        .collect();
+    let ignored_full_path = get_ignored_paths(&ignore_directories, &simplified_dirs);
 
     let output_format = config.get_output_format(&options);
 
@@ -241,32 +365,159 @@ fn main() {
         None => HashSet::new(),
     };
 
-    let filter_modified_time = config.get_modified_time_operator(&options);
-    let filter_accessed_time = config.get_accessed_time_operator(&options);
-    let filter_changed_time = config.get_changed_time_operator(&options);
+    let size_range = config.get_size_range(&options);
+    let users = config.get_users(&options);
+    let groups = config.get_groups(&options);
+    let perm = config.get_perm(&options);
+    let using_a_filter = !filter_regexs.is_empty()
+        || !invert_filter_regexs.is_empty()
+        || options.filter_where.is_some()
+        || size_range.is_some()
+        || !users.is_empty()
+        || !groups.is_empty()
+        || perm.is_some();
+    let time_filters = [
+        (
+            FileTime::Modified,
+            config.get_modified_time_operator(&options),
+        ),
+        (
+            FileTime::Accessed,
+            config.get_accessed_time_operator(&options),
+        ),
+        (
+            FileTime::Changed,
+            config.get_changed_time_operator(&options),
+        ),
+        (
+            FileTime::Created,
+            config.get_created_time_operator(&options),
+        ),
+    ]
+    .into_iter()
+    .chain([
+        (
+            FileTime::Modified,
+            config
+                .get_newer(&options)
+                .map(|t| (Operator::GreaterThan, t)),
+        ),
+        (
+            FileTime::Modified,
+            config.get_older(&options).map(|t| (Operator::LessThan, t)),
+        ),
+    ])
+    .filter_map(|(file_time, filter)| {
+        filter.map(|(operator, time)| FilterExpr::Time(file_time, operator, time))
+    });
+    let owner_filters = [
+        (!users.is_empty()).then(|| FilterExpr::UserIn(users)),
+        (!groups.is_empty()).then(|| FilterExpr::GroupIn(groups)),
+        perm.map(FilterExpr::Mode),
+    ];
+    let filters = [
+        (
+            FilterKind::Where,
+            get_where_value(options.filter_where.as_ref()),
+        ),
+        (
+            FilterKind::Regex,
+            FilterExpr::any_matches(filter_regexs, options.match_on),
+        ),
+        (
+            FilterKind::InvertRegex,
+            FilterExpr::any_matches(invert_filter_regexs, options.match_on).map(FilterExpr::negate),
+        ),
+        (
+            FilterKind::Size,
+            size_range.map(|(min, max)| FilterExpr::Size(min, max)),
+        ),
+        (FilterKind::Time, time_filters.reduce(FilterExpr::and)),
+        (
+            FilterKind::Owner,
+            owner_filters.into_iter().flatten().reduce(FilterExpr::and),
+        ),
+    ]
+    .into_iter()
+    .filter_map(|(kind, filter)| filter.map(|f| (kind, f)))
+    .collect();
+
+    platform::set_disk_size_wanted(
+        config.get_compare_sizes(&options) || config.get_columns(&options).contains(&Column::Disk),
+    );
 
     let walk_data = WalkData {
         ignore_directories: ignored_full_path,
-        filter_regex: &filter_regexs,
-        invert_filter_regex: &invert_filter_regexs,
+        exclude_globs,
+        filters,
         allowed_filesystems,
-        filter_modified_time,
-        filter_accessed_time,
-        filter_changed_time,
+        excluded_mounts,
         use_apparent_size: config.get_apparent_size(&options),
         by_filecount,
         by_filetime: &by_filetime,
+        by_size_difference: options.size_difference,
         ignore_hidden,
+        gitignore: config.get_gitignore(&options),
+        use_dustignore: config.get_use_dustignore(&options),
         follow_links,
         progress_data: indicator.data.clone(),
         errors: errors_for_rayon,
+        skipped: (show_reconciliation || show_excluded)
+            .then(|| Arc::new(Mutex::new(SkippedData::default()))),
     };
 
     let threads_to_use = config.get_threads(&options);
     let stack_size = config.get_custom_stack_size(&options);
 
     init_rayon(&stack_size, &threads_to_use).install(|| {
-        let top_level_nodes = walk_it(simplified_dirs, &walk_data);
+        let mut top_level_nodes = walk_it(simplified_dirs, &walk_data);
+
+        let deleted = if deleted_files_devices.is_empty() {
+            None
+        } else {
+            get_deleted_open_files(&deleted_files_devices, &walk_data)
+        };
+
+        let skipped = walk_data.skipped.as_ref().map(|s| s.lock().unwrap());
+        let excluded = skipped
+            .as_deref()
+            .filter(|_| show_excluded)
+            .map(get_excluded_report);
+        let reconciliation = skipped
+            .as_deref()
+            .filter(|_| show_reconciliation)
+            .map(|skipped| {
+                reconcile(
+                    &filesystems,
+                    &top_level_nodes,
+                    skipped,
+                    walk_data.errors.lock().unwrap().no_permissions.len(),
+                    deleted.as_ref().map_or(0, |d| d.disk_size),
+                    !walk_data.use_apparent_size,
+                )
+            });
+
+        if show_deleted_open_files && let Some(deleted) = deleted {
+            top_level_nodes.push(deleted);
+        }
+
+        let ancestors = match options.focus {
+            Some(ref focus) => {
+                let by_filetime = walk_data.by_filetime.is_some();
+                match focus_on(top_level_nodes, &normalize_path(focus), by_filetime) {
+                    Some((node, ancestors)) => {
+                        top_level_nodes = vec![node];
+                        Some(ancestors)
+                    }
+                    None => {
+                        indicator.stop();
+                        eprintln!("Focus path is not in the scanned tree: {focus}");
+                        process::exit(1)
+                    }
+                }
+            }
+            None => None,
+        };
 
         let tree = match summarize_file_types {
             true => get_all_file_types(&top_level_nodes, number_of_lines, walk_data.by_filetime),
@@ -277,15 +528,26 @@ fn main() {
                     only_file: config.get_only_file(&options),
                     number_of_lines,
                     depth,
-                    using_a_filter: !filter_regexs.is_empty() || !invert_filter_regexs.is_empty(),
+                    using_a_filter,
                     short_paths: !config.get_full_paths(&options),
+                    show_remainder: config.get_show_remainder(&options),
+                    output_format: if by_filecount {
+                        "count".to_string()
+                    } else {
+                        config.get_output_format(&options)
+                    },
+                    per_dir: config.get_per_dir(&options),
+                    sort_by: options.sort,
                 };
-                get_biggest(
-                    top_level_nodes,
-                    agg_data,
-                    walk_data.by_filetime,
-                    keep_collapsed,
-                )
+                match options.flat {
+                    Some(flat) => get_flat(top_level_nodes, agg_data, flat, walk_data.by_filetime),
+                    None => get_biggest(
+                        top_level_nodes,
+                        agg_data,
+                        walk_data.by_filetime,
+                        keep_collapsed,
+                    ),
+                }
             }
         };
 
@@ -295,6 +557,9 @@ fn main() {
         let print_errors = config.get_print_errors(&options);
         let final_errors = walk_data.errors.lock().unwrap();
         print_any_errors(print_errors, &final_errors);
+        if walk_data.by_filetime == &Some(FileTime::Created) && tree.newest_btime.is_none() {
+            eprintln!("Creation time is not recorded by this filesystem, shown as unknown");
+        }
 
         if tree.children.is_empty() && !final_errors.file_not_found.is_empty() {
             std::process::exit(1)
@@ -303,6 +568,13 @@ fn main() {
                 config,
                 options,
                 tree,
+                Reports {
+                    filesystems,
+                    mount_point_types,
+                    reconciliation,
+                    excluded,
+                    ancestors,
+                },
                 walk_data.by_filecount,
                 is_colors,
                 terminal_width,
@@ -315,6 +587,7 @@ fn print_output(
     config: Config,
     options: Cli,
     tree: DisplayNode,
+    reports: Reports,
     by_filecount: bool,
     is_colors: bool,
     terminal_width: usize,
@@ -329,27 +602,89 @@ fn print_output(
                 wrapped.replace(output_format);
             }
         });
-        println!("{}", serde_json::to_string(&tree).unwrap());
+        let show_filesystems = config.get_filesystem_info(&options);
+        let percent_of_parent = config.get_percent_of_parent(&options);
+        let filesystem_percent = config.get_filesystem_percent(&options);
+        if show_filesystems
+            || percent_of_parent
+            || filesystem_percent
+            || !reports.mount_point_types.is_empty()
+            || reports.reconciliation.is_some()
+            || reports.excluded.is_some()
+            || reports.ancestors.is_some()
+        {
+            let mut json = serde_json::to_value(&tree).unwrap();
+            if percent_of_parent {
+                add_percents(&mut json, &tree, tree.size, tree.size);
+            }
+            if filesystem_percent {
+                add_filesystem_percents(&mut json, &tree, &reports.filesystems);
+            }
+            add_fs_types(&mut json, &tree, &reports.mount_point_types);
+            if show_filesystems {
+                json["filesystems"] = serde_json::to_value(&reports.filesystems).unwrap();
+            }
+            if let Some(reconciliation) = &reports.reconciliation {
+                json["reconciliation"] = serde_json::to_value(reconciliation).unwrap();
+            }
+            if let Some(excluded) = &reports.excluded {
+                json["excluded"] = serde_json::to_value(excluded).unwrap();
+            }
+            if let Some(ancestors) = &reports.ancestors {
+                json["ancestors"] = serde_json::to_value(ancestors).unwrap();
+            }
+            println!("{json}");
+        } else {
+            println!("{}", serde_json::to_string(&tree).unwrap());
+        }
     } else {
+        if config.get_filesystem_info(&options) {
+            draw_filesystem_info(&reports.filesystems, &output_format);
+        }
+        let report_output_format = output_format.clone();
+        if let Some(ancestors) = &reports.ancestors {
+            draw_breadcrumb(ancestors, &report_output_format, by_filecount);
+        }
+
+        let flat = options.flat.is_some();
         let idd = InitialDisplayData {
-            short_paths: !config.get_full_paths(&options),
+            short_paths: !flat && !config.get_full_paths(&options),
             is_reversed: !config.get_reverse(&options),
             colors_on: is_colors,
             dim: config.get_dim(&options),
+            heatmap: config.get_heatmap(&options),
             by_filecount,
             by_filetime: config.get_filetime(&options),
+            compare_sizes: config.get_compare_sizes(&options),
+            columns: config.get_columns(&options),
             is_screen_reader: config.get_screen_reader(&options),
             output_format,
             bars_on_right: config.get_bars_on_right(&options),
+            filesystem_percent: config.get_filesystem_percent(&options),
+            percent_of_parent: config.get_percent_of_parent(&options),
+            filesystems: reports.filesystems,
+            mount_point_types: reports.mount_point_types,
+            theme: config.get_theme(),
         };
 
-        draw_it(
-            idd,
-            &tree,
-            config.get_no_bars(&options),
-            terminal_width,
-            config.get_skip_total(&options),
-        )
+        if flat {
+            draw_flat(idd, &tree, config.get_no_bars(&options), terminal_width);
+        } else {
+            draw_it(
+                idd,
+                &tree,
+                config.get_no_bars(&options),
+                terminal_width,
+                config.get_skip_total(&options),
+            );
+        }
+
+        if let Some(reconciliation) = &reports.reconciliation {
+            draw_reconciliation(reconciliation, &report_output_format);
+        }
+        if let Some(excluded) = &reports.excluded {
+            draw_excluded_report(excluded, &report_output_format);
+        }
     }
 }
 
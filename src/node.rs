@@ -1,16 +1,26 @@
 use crate::dir_walker::WalkData;
+use crate::filter_expr::Candidate;
 use crate::platform::get_metadata;
-use crate::utils::is_filtered_out_due_to_file_time;
-use crate::utils::is_filtered_out_due_to_invert_regex;
-use crate::utils::is_filtered_out_due_to_regex;
+use crate::platform::get_ownership;
 
 use std::cmp::Ordering;
+use std::path::Path;
 use std::path::PathBuf;
 
 #[derive(Debug, Eq, Clone)]
 pub struct Node {
     pub name: PathBuf,
     pub size: u64,
+    pub disk_size: u64,
+    pub apparent_size: u64,
+    // Files and directories below, a directory counting itself as find does. The
+    // newest and oldest modification times, 0 and i64::MAX when there are none
+    pub file_count: u64,
+    pub dir_count: u64,
+    pub newest_mtime: i64,
+    pub oldest_mtime: i64,
+    // The newest birth time, None when none was recorded for anything below
+    pub newest_btime: Option<i64>,
     pub children: Vec<Node>,
     pub inode_device: Option<(u64, u64)>,
     pub depth: usize,
@@ -21,6 +31,7 @@ pub enum FileTime {
     Modified,
     Accessed,
     Changed,
+    Created,
 }
 
 impl From<crate::cli::FileTime> for FileTime {
@@ -29,6 +40,7 @@ impl From<crate::cli::FileTime> for FileTime {
             crate::cli::FileTime::Modified => Self::Modified,
             crate::cli::FileTime::Accessed => Self::Accessed,
             crate::cli::FileTime::Changed => Self::Changed,
+            crate::cli::FileTime::Created => Self::Created,
         }
     }
 }
@@ -36,6 +48,7 @@ impl From<crate::cli::FileTime> for FileTime {
 #[allow(clippy::too_many_arguments)]
 pub fn build_node(
     dir: PathBuf,
+    root: &Path,
     children: Vec<Node>,
     is_symlink: bool,
     is_file: bool,
@@ -45,27 +58,42 @@ pub fn build_node(
     let use_apparent_size = walk_data.use_apparent_size;
     let by_filecount = walk_data.by_filecount;
     let by_filetime = &walk_data.by_filetime;
+    let by_size_difference = walk_data.by_size_difference;
 
-    get_metadata(
-        &dir,
-        use_apparent_size,
-        walk_data.follow_links && is_symlink,
-    )
-    .map(|data| {
+    get_metadata(&dir, walk_data.follow_links && is_symlink).map(|data| {
         let inode_device = data.1;
+        let (disk_size, apparent_size) = data.0;
 
-        let size = if is_filtered_out_due_to_regex(walk_data.filter_regex, &dir)
-            || is_filtered_out_due_to_invert_regex(walk_data.invert_filter_regex, &dir)
-            || by_filecount && !is_file
-            || [
-                (&walk_data.filter_modified_time, data.2.0),
-                (&walk_data.filter_accessed_time, data.2.1),
-                (&walk_data.filter_changed_time, data.2.2),
-            ]
+        // Links which are not followed are neither files nor directories
+        let is_dir = !is_file && (!is_symlink || walk_data.follow_links);
+
+        // Everything else was already matched by the walk before it got here
+        let filters: Vec<_> = walk_data
+            .filters
             .iter()
-            .any(|(filter_time, actual_time)| {
-                is_filtered_out_due_to_file_time(filter_time, *actual_time)
-            }) {
+            .map(|(_, filter)| filter)
+            .filter(|filter| is_dir && filter.applies_to_dirs())
+            .collect();
+        let is_filtered_out = !filters.is_empty() && {
+            let candidate = Candidate {
+                path: &dir,
+                root,
+                size: if use_apparent_size {
+                    apparent_size
+                } else {
+                    disk_size
+                },
+                times: data.2,
+                ownership: if filters.iter().any(|f| f.needs_ownership()) {
+                    get_ownership(&dir, walk_data.follow_links && is_symlink)
+                } else {
+                    None
+                },
+            };
+            filters.iter().any(|filter| !filter.is_match(&candidate))
+        };
+
+        let size = if is_filtered_out || by_filecount && !is_file {
             0
         } else if by_filecount {
             1
@@ -74,15 +102,39 @@ pub fn build_node(
                 Some(FileTime::Modified) => data.2.0.unsigned_abs(),
                 Some(FileTime::Accessed) => data.2.1.unsigned_abs(),
                 Some(FileTime::Changed) => data.2.2.unsigned_abs(),
+                // Unknown birth times count as 0, newest_btime tells them apart
+                Some(FileTime::Created) => data.2.3.map_or(0, i64::unsigned_abs),
                 None => unreachable!(),
             }
+        } else if by_size_difference {
+            disk_size.abs_diff(apparent_size)
+        } else if use_apparent_size {
+            apparent_size
+        } else {
+            disk_size
+        };
+        let (disk_size, apparent_size, file_count, mtime, btime) = if is_filtered_out {
+            (0, 0, 0, None, None)
         } else {
-            data.0
+            (
+                disk_size,
+                apparent_size,
+                is_file as u64,
+                Some(data.2.0),
+                data.2.3,
+            )
         };
 
         Node {
             name: dir,
             size,
+            disk_size,
+            apparent_size,
+            file_count,
+            dir_count: is_dir as u64,
+            newest_mtime: mtime.unwrap_or(0),
+            oldest_mtime: mtime.unwrap_or(i64::MAX),
+            newest_btime: btime,
             children,
             inode_device,
             depth,
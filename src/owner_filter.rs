@@ -0,0 +1,125 @@
+use crate::platform::get_group_id;
+use crate::platform::get_user_id;
+
+// The parsing shared by --user, --group, --perm and the same fields of --where
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ModeMatch {
+    // The permission bits are exactly these, as find -perm 644
+    Exact(u32),
+    // All of these bits are set, as find -perm -4000
+    All(u32),
+    // Any of these bits is set, as find -perm /022
+    Any(u32),
+}
+
+impl ModeMatch {
+    pub fn is_match(&self, mode: u32) -> bool {
+        let mode = mode & 0o7777;
+        match *self {
+            ModeMatch::Exact(bits) => mode == bits,
+            ModeMatch::All(bits) => mode & bits == bits,
+            // As with find, no bits at all matches every file
+            ModeMatch::Any(bits) => bits == 0 || mode & bits != 0,
+        }
+    }
+}
+
+// A user name or a numeric uid, names are resolved on this machine
+pub fn parse_user(value: &str) -> Option<u32> {
+    value.parse().ok().or_else(|| get_user_id(value))
+}
+
+pub fn parse_group(value: &str) -> Option<u32> {
+    value.parse().ok().or_else(|| get_group_id(value))
+}
+
+// Octal (644, -4000, /022) or symbolic (u+s, -g+w, /o+w,a+x) permission bits
+pub fn parse_mode(value: &str) -> Option<ModeMatch> {
+    let (make, bits): (fn(u32) -> ModeMatch, &str) = match value.as_bytes().first() {
+        Some(b'-') => (ModeMatch::All, &value[1..]),
+        Some(b'/') => (ModeMatch::Any, &value[1..]),
+        _ => (ModeMatch::Exact, value),
+    };
+    let bits = if !bits.is_empty() && bits.bytes().all(|b| (b'0'..=b'7').contains(&b)) {
+        u32::from_str_radix(bits, 8).ok().filter(|b| *b <= 0o7777)?
+    } else {
+        parse_symbolic_mode(bits)?
+    };
+    Some(make(bits))
+}
+
+// Comma separated clauses of who (u, g, o, a or none for all), + or = and the
+// permissions (r, w, x, s, t)
+fn parse_symbolic_mode(value: &str) -> Option<u32> {
+    let mut bits = 0;
+    for clause in value.split(',') {
+        let split = clause.find(['+', '='])?;
+        let (who, permissions) = (&clause[..split], &clause[split + 1..]);
+        let who_mask = if who.is_empty() {
+            0o7777
+        } else {
+            who.chars().try_fold(0, |mask, c| match c {
+                'u' => Some(mask | 0o4700),
+                'g' => Some(mask | 0o2070),
+                'o' => Some(mask | 0o1007),
+                'a' => Some(mask | 0o7777),
+                _ => None,
+            })?
+        };
+        let permission_bits = permissions.chars().try_fold(0, |bits, c| match c {
+            'r' => Some(bits | 0o444),
+            'w' => Some(bits | 0o222),
+            'x' => Some(bits | 0o111),
+            's' => Some(bits | 0o6000),
+            't' => Some(bits | 0o1000),
+            _ => None,
+        })?;
+        bits |= who_mask & permission_bits;
+    }
+    Some(bits)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_mode() {
+        assert_eq!(parse_mode("644"), Some(ModeMatch::Exact(0o644)));
+        assert_eq!(parse_mode("-4000"), Some(ModeMatch::All(0o4000)));
+        assert_eq!(parse_mode("/o+w"), Some(ModeMatch::Any(0o002)));
+        assert_eq!(parse_mode("-u+s"), Some(ModeMatch::All(0o4000)));
+        assert_eq!(parse_mode("/ug+s"), Some(ModeMatch::Any(0o6000)));
+        assert_eq!(parse_mode("u=rw,go=r"), Some(ModeMatch::Exact(0o644)));
+        assert_eq!(parse_mode("+x"), Some(ModeMatch::Exact(0o111)));
+        assert_eq!(parse_mode("o+t"), Some(ModeMatch::Exact(0o1000)));
+        assert_eq!(parse_mode("17777"), None);
+        assert_eq!(parse_mode("u+q"), None);
+        assert_eq!(parse_mode("world"), None);
+        assert_eq!(parse_mode("-"), None);
+    }
+
+    #[test]
+    fn test_mode_match() {
+        let world_writable = parse_mode("/o+w").unwrap();
+        assert!(world_writable.is_match(0o100666));
+        assert!(!world_writable.is_match(0o100644));
+        let setuid = parse_mode("-4000").unwrap();
+        assert!(setuid.is_match(0o104755));
+        assert!(!setuid.is_match(0o102755));
+        assert!(parse_mode("644").unwrap().is_match(0o100644));
+        assert!(!parse_mode("644").unwrap().is_match(0o100664));
+        assert!(parse_mode("/000").unwrap().is_match(0o100000));
+    }
+
+    #[cfg(target_family = "unix")]
+    #[test]
+    fn test_parse_user_and_group() {
+        assert_eq!(parse_user("0"), Some(0));
+        assert_eq!(parse_user("root"), Some(0));
+        assert_eq!(parse_group("0"), Some(0));
+        assert_eq!(parse_user("no-such-user-for-dust"), None);
+        assert_eq!(parse_group("no-such-group-for-dust"), None);
+    }
+}
@@ -2,6 +2,15 @@
 use std::fs;
 
 use std::path::Path;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+// Windows finds the disk size of a file by opening it, which is slow, so it is
+// only done when the disk size is shown next to the length
+static DISK_SIZE_WANTED: AtomicBool = AtomicBool::new(false);
+
+pub fn set_disk_size_wanted(wanted: bool) {
+    DISK_SIZE_WANTED.store(wanted, Ordering::Relaxed);
+}
 
 #[cfg(target_family = "unix")]
 fn get_block_size() -> u64 {
@@ -10,8 +19,20 @@ fn get_block_size() -> u64 {
     512
 }
 
+type DiskAndApparentSize = (u64, u64);
 type InodeAndDevice = (u64, u64);
-type FileTime = (i64, i64, i64);
+// Modified, accessed, changed and, where the filesystem reports it, birth time
+type FileTime = (i64, i64, i64, Option<i64>);
+
+#[cfg(target_family = "unix")]
+fn get_birth_time(md: &fs::Metadata) -> Option<i64> {
+    // Rust reads this with statx on Linux, it is an error where it is not recorded
+    let created = md.created().ok()?;
+    match created.duration_since(std::time::UNIX_EPOCH) {
+        Ok(d) => Some(d.as_secs() as i64),
+        Err(e) => Some(-(e.duration().as_secs() as i64)),
+    }
+}
 
 #[cfg(target_family = "windows")]
 fn filetime_to_unix_seconds(filetime: u64) -> i64 {
@@ -24,9 +45,8 @@ fn filetime_to_unix_seconds(filetime: u64) -> i64 {
 #[cfg(target_family = "unix")]
 pub fn get_metadata<P: AsRef<Path>>(
     path: P,
-    use_apparent_size: bool,
     follow_links: bool,
-) -> Option<(u64, Option<InodeAndDevice>, FileTime)> {
+) -> Option<(DiskAndApparentSize, Option<InodeAndDevice>, FileTime)> {
     use std::os::unix::fs::MetadataExt;
     let metadata = if follow_links {
         path.as_ref().metadata()
@@ -36,35 +56,28 @@ pub fn get_metadata<P: AsRef<Path>>(
     match metadata {
         Ok(md) => {
             let file_size = md.len();
-            if use_apparent_size {
-                Some((
-                    file_size,
-                    Some((md.ino(), md.dev())),
-                    (md.mtime(), md.atime(), md.ctime()),
-                ))
+            // On NTFS mounts, the reported block count can be unexpectedly large.
+            // To avoid overestimating disk usage, cap the allocated size to what the
+            // file should occupy based on the file system I/O block size (blksize).
+            // Related: https://github.com/bootandy/dust/issues/295
+            let blksize = md.blksize();
+            let target_size = file_size.div_ceil(blksize) * blksize;
+            let reported_size = md.blocks() * get_block_size();
+
+            // File systems can pre-allocate more space for a file than what would be necessary
+            let pre_allocation_buffer = blksize * 65536;
+            let max_size = target_size + pre_allocation_buffer;
+            let allocated_size = if reported_size > max_size {
+                target_size
             } else {
-                // On NTFS mounts, the reported block count can be unexpectedly large.
-                // To avoid overestimating disk usage, cap the allocated size to what the
-                // file should occupy based on the file system I/O block size (blksize).
-                // Related: https://github.com/bootandy/dust/issues/295
-                let blksize = md.blksize();
-                let target_size = file_size.div_ceil(blksize) * blksize;
-                let reported_size = md.blocks() * get_block_size();
-
-                // File systems can pre-allocate more space for a file than what would be necessary
-                let pre_allocation_buffer = blksize * 65536;
-                let max_size = target_size + pre_allocation_buffer;
-                let allocated_size = if reported_size > max_size {
-                    target_size
-                } else {
-                    reported_size
-                };
-                Some((
-                    allocated_size,
-                    Some((md.ino(), md.dev())),
-                    (md.mtime(), md.atime(), md.ctime()),
-                ))
-            }
+                reported_size
+            };
+            // Both sizes are always returned so sparse and compressed files can be spotted
+            Some((
+                (allocated_size, file_size),
+                Some((md.ino(), md.dev())),
+                (md.mtime(), md.atime(), md.ctime(), get_birth_time(&md)),
+            ))
         }
         Err(_e) => None,
     }
@@ -73,9 +86,8 @@ pub fn get_metadata<P: AsRef<Path>>(
 #[cfg(target_family = "windows")]
 pub fn get_metadata<P: AsRef<Path>>(
     path: P,
-    use_apparent_size: bool,
     follow_links: bool,
-) -> Option<(u64, Option<InodeAndDevice>, FileTime)> {
+) -> Option<(DiskAndApparentSize, Option<InodeAndDevice>, FileTime)> {
     // On windows opening the file to get size, file ID and volume can be very
     // expensive because 1) it causes a few system calls, and more importantly 2) it can cause
     // windows defender to scan the file.
@@ -141,35 +153,29 @@ pub fn get_metadata<P: AsRef<Path>>(
 
     fn get_metadata_expensive(
         path: &Path,
-        use_apparent_size: bool,
-    ) -> Option<(u64, Option<InodeAndDevice>, FileTime)> {
+    ) -> Option<(DiskAndApparentSize, Option<InodeAndDevice>, FileTime)> {
+        use filesize::PathExt;
         use winapi_util::file::information;
 
         let h = handle_from_path_limited(path).ok()?;
         let info = information(&h).ok()?;
 
-        if use_apparent_size {
-            use filesize::PathExt;
-            Some((
-                path.size_on_disk().ok()?,
-                Some((info.file_index(), info.volume_serial_number())),
-                (
-                    filetime_to_unix_seconds(info.last_write_time().unwrap()),
-                    filetime_to_unix_seconds(info.last_access_time().unwrap()),
-                    filetime_to_unix_seconds(info.creation_time().unwrap()),
-                ),
-            ))
+        // Unless the disk size is wanted both sizes are the length, as before
+        let disk_size = if DISK_SIZE_WANTED.load(Ordering::Relaxed) {
+            path.size_on_disk().ok()?
         } else {
-            Some((
-                info.file_size(),
-                Some((info.file_index(), info.volume_serial_number())),
-                (
-                    filetime_to_unix_seconds(info.last_write_time().unwrap()),
-                    filetime_to_unix_seconds(info.last_access_time().unwrap()),
-                    filetime_to_unix_seconds(info.creation_time().unwrap()),
-                ),
-            ))
-        }
+            info.file_size()
+        };
+        Some((
+            (disk_size, info.file_size()),
+            Some((info.file_index(), info.volume_serial_number())),
+            (
+                filetime_to_unix_seconds(info.last_write_time().unwrap()),
+                filetime_to_unix_seconds(info.last_access_time().unwrap()),
+                filetime_to_unix_seconds(info.creation_time().unwrap()),
+                Some(filetime_to_unix_seconds(info.creation_time().unwrap())),
+            ),
+        ))
     }
 
     use std::os::windows::fs::MetadataExt;
@@ -205,21 +211,90 @@ pub fn get_metadata<P: AsRef<Path>>(
             if ((attr_filtered & FILE_ATTRIBUTE_ARCHIVE) != 0
                 || (attr_filtered & FILE_ATTRIBUTE_DIRECTORY) != 0
                 || md.file_attributes() == FILE_ATTRIBUTE_NORMAL)
-                && !((attr_filtered & IS_PROBABLY_ONEDRIVE != 0) && use_apparent_size)
+                // The disk size of these differs from their length
+                && !(attr_filtered & IS_PROBABLY_ONEDRIVE != 0
+                    && DISK_SIZE_WANTED.load(Ordering::Relaxed))
             {
                 Some((
-                    md.len(),
+                    (md.len(), md.len()),
                     None,
                     (
                         filetime_to_unix_seconds(md.last_write_time()),
                         filetime_to_unix_seconds(md.last_access_time()),
                         filetime_to_unix_seconds(md.creation_time()),
+                        Some(filetime_to_unix_seconds(md.creation_time())),
                     ),
                 ))
             } else {
-                get_metadata_expensive(path, use_apparent_size)
+                get_metadata_expensive(path)
             }
         }
-        _ => get_metadata_expensive(path, use_apparent_size),
+        _ => get_metadata_expensive(path),
     }
 }
+
+// Owner, group and mode bits, only unix has them
+#[cfg(target_family = "unix")]
+pub fn get_ownership<P: AsRef<Path>>(path: P, follow_links: bool) -> Option<(u32, u32, u32)> {
+    use std::os::unix::fs::MetadataExt;
+    let metadata = if follow_links {
+        path.as_ref().metadata()
+    } else {
+        path.as_ref().symlink_metadata()
+    };
+    metadata.ok().map(|md| (md.uid(), md.gid(), md.mode()))
+}
+
+#[cfg(target_family = "windows")]
+pub fn get_ownership<P: AsRef<Path>>(_path: P, _follow_links: bool) -> Option<(u32, u32, u32)> {
+    None
+}
+
+// Looks up a user with the system's own resolver, so NSS sources such as LDAP work
+#[cfg(target_family = "unix")]
+pub fn get_user_id(name: &str) -> Option<u32> {
+    let name = std::ffi::CString::new(name).ok()?;
+    let mut buffer = vec![0 as libc::c_char; 16 * 1024];
+    let mut passwd: libc::passwd = unsafe { std::mem::zeroed() };
+    let mut result = std::ptr::null_mut();
+    // SAFETY: all the pointers are valid for the call and buffer outlives it
+    let rc = unsafe {
+        libc::getpwnam_r(
+            name.as_ptr(),
+            &mut passwd,
+            buffer.as_mut_ptr(),
+            buffer.len(),
+            &mut result,
+        )
+    };
+    (rc == 0 && !result.is_null()).then_some(passwd.pw_uid)
+}
+
+#[cfg(target_family = "unix")]
+pub fn get_group_id(name: &str) -> Option<u32> {
+    let name = std::ffi::CString::new(name).ok()?;
+    let mut buffer = vec![0 as libc::c_char; 16 * 1024];
+    let mut group: libc::group = unsafe { std::mem::zeroed() };
+    let mut result = std::ptr::null_mut();
+    // SAFETY: all the pointers are valid for the call and buffer outlives it
+    let rc = unsafe {
+        libc::getgrnam_r(
+            name.as_ptr(),
+            &mut group,
+            buffer.as_mut_ptr(),
+            buffer.len(),
+            &mut result,
+        )
+    };
+    (rc == 0 && !result.is_null()).then_some(group.gr_gid)
+}
+
+#[cfg(target_family = "windows")]
+pub fn get_user_id(_name: &str) -> Option<u32> {
+    None
+}
+
+#[cfg(target_family = "windows")]
+pub fn get_group_id(_name: &str) -> Option<u32> {
+    None
+}
@@ -0,0 +1,199 @@
+use serde::Serialize;
+use std::collections::HashSet;
+use std::path::PathBuf;
+
+use crate::dir_walker::SkippedData;
+use crate::display::human_readable_number;
+use crate::filesystem::FilesystemInfo;
+use crate::node::Node;
+
+// Compares what the scan counted with the used space of the filesystem (as
+// reported by df) and attributes as much of the difference as possible.
+// All sizes are bytes on disk.
+#[derive(Debug, Serialize)]
+pub struct Reconciliation {
+    pub filesystem_used: u64,
+    pub counted: u64,
+    pub difference: i64,
+    pub excluded: u64,
+    pub deleted_open_files: u64,
+    // Extra links to the same inode, only part of the difference if they were counted twice
+    pub hardlinks: u64,
+    pub hardlinks_counted_once: bool,
+    pub no_permissions: usize,
+    pub other_filesystems: usize,
+    pub unexplained: i64,
+    pub not_mount_points: Vec<PathBuf>,
+}
+
+pub fn reconcile(
+    filesystems: &[FilesystemInfo],
+    top_level_nodes: &[Node],
+    skipped: &SkippedData,
+    no_permissions: usize,
+    deleted_open_files: u64,
+    hardlinks_counted_once: bool,
+) -> Reconciliation {
+    // Several inputs can share a filesystem, only count its used space once
+    let mut seen_mounts = HashSet::new();
+    let filesystem_used = filesystems
+        .iter()
+        .filter(|fs| seen_mounts.insert((fs.mount_point.clone(), fs.total)))
+        .map(|fs| fs.used)
+        .sum::<u64>();
+
+    let counted = top_level_nodes
+        .iter()
+        .filter(|n| filesystems.iter().any(|fs| fs.root == n.name))
+        .map(|n| n.disk_size)
+        .sum::<u64>();
+
+    let excluded = skipped
+        .excluded
+        .values()
+        .map(|(_, bytes)| bytes)
+        .sum::<u64>();
+    let hardlinks = skipped.hardlinks.1;
+    let hardlinks_counted_twice = if hardlinks_counted_once { 0 } else { hardlinks };
+
+    let difference = filesystem_used as i64 - counted as i64;
+    let unexplained =
+        difference - excluded as i64 - deleted_open_files as i64 + hardlinks_counted_twice as i64;
+
+    let not_mount_points = filesystems
+        .iter()
+        .filter(|fs| match &fs.mount_point {
+            Some(mount_point) => std::fs::canonicalize(&fs.root).ok().as_ref() != Some(mount_point),
+            None => true,
+        })
+        .map(|fs| fs.root.clone())
+        .collect();
+
+    Reconciliation {
+        filesystem_used,
+        counted,
+        difference,
+        excluded,
+        deleted_open_files,
+        hardlinks,
+        hardlinks_counted_once,
+        no_permissions,
+        other_filesystems: skipped.other_filesystems.len(),
+        unexplained,
+        not_mount_points,
+    }
+}
+
+pub fn draw_reconciliation(reconciliation: &Reconciliation, output_format: &str) {
+    println!("{}", format_reconciliation(reconciliation, output_format));
+}
+
+fn format_reconciliation(r: &Reconciliation, output_format: &str) -> String {
+    let size = |s: u64| human_readable_number(s, output_format);
+    let signed_size = |s: i64| {
+        let sign = if s < 0 { "-" } else { "" };
+        format!(
+            "{sign}{}",
+            human_readable_number(s.unsigned_abs(), output_format)
+        )
+    };
+    let hardlinks = if r.hardlinks_counted_once {
+        format!("{} (counted once, like df)", size(r.hardlinks))
+    } else {
+        format!("-{} (counted more than once)", size(r.hardlinks))
+    };
+
+    let mut lines = vec![
+        "Difference from filesystem used space:".to_string(),
+        format!("  filesystem used:          {}", size(r.filesystem_used)),
+        format!("  counted by dust:          {}", size(r.counted)),
+        format!("  difference:               {}", signed_size(r.difference)),
+        format!("  excluded paths:           {}", size(r.excluded)),
+        format!("  deleted but still open:   {}", size(r.deleted_open_files)),
+        format!("  hardlinks:                {hardlinks}"),
+        format!(
+            "  permission denied:        {} directories (size unknown)",
+            r.no_permissions
+        ),
+        format!(
+            "  other filesystems:        {} mount points (files beneath them are hidden)",
+            r.other_filesystems
+        ),
+        format!("  unexplained:              {}", signed_size(r.unexplained)),
+    ];
+    for root in r.not_mount_points.iter() {
+        lines.push(format!(
+            "Note: {} is not a mount point, space used elsewhere on its filesystem is unexplained",
+            root.display()
+        ));
+    }
+    lines.join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn node(name: &str, disk_size: u64) -> Node {
+        Node {
+            name: PathBuf::from(name),
+            size: disk_size,
+            disk_size,
+            apparent_size: disk_size,
+            file_count: 1,
+            dir_count: 0,
+            newest_mtime: 0,
+            oldest_mtime: i64::MAX,
+            newest_btime: None,
+            children: vec![],
+            inode_device: None,
+            depth: 0,
+        }
+    }
+
+    fn filesystem(root: &str, used: u64) -> FilesystemInfo {
+        FilesystemInfo {
+            root: PathBuf::from(root),
+            mount_point: Some(PathBuf::from("/")),
+            fs_type: Some("ext4".into()),
+            total: 10_000,
+            used,
+            free: 10_000 - used,
+            inodes_total: 100,
+            inodes_used: 10,
+        }
+    }
+
+    #[test]
+    fn test_reconcile_attributes_difference() {
+        let filesystems = vec![filesystem("/a", 5000), filesystem("/b", 5000)];
+        let nodes = vec![node("/a", 1000), node("/b", 2000), node("(other)", 500)];
+        let mut skipped = SkippedData::default();
+        skipped
+            .excluded
+            .insert(crate::dir_walker::SkipReason::IgnoredPath, (3, 700));
+        skipped.hardlinks = (1, 100);
+
+        // The filesystem is shared so its used space is only counted once
+        let r = reconcile(&filesystems, &nodes, &skipped, 2, 300, true);
+        assert_eq!(r.filesystem_used, 5000);
+        assert_eq!(r.counted, 3000);
+        assert_eq!(r.difference, 2000);
+        assert_eq!(r.unexplained, 1000);
+        assert_eq!(r.no_permissions, 2);
+
+        // Hardlinks counted more than once make the scan bigger than df
+        let r = reconcile(&filesystems, &nodes, &skipped, 2, 300, false);
+        assert_eq!(r.unexplained, 1100);
+    }
+
+    #[test]
+    fn test_format_reconciliation_negative_difference() {
+        let filesystems = vec![filesystem("/a", 1024)];
+        let nodes = vec![node("/a", 3072)];
+        let r = reconcile(&filesystems, &nodes, &SkippedData::default(), 0, 0, true);
+        let s = format_reconciliation(&r, "");
+        assert!(s.contains("  difference:               -2.0Ki"), "{s}");
+        assert!(s.contains("  unexplained:              -2.0Ki"), "{s}");
+    }
+}
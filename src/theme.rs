@@ -0,0 +1,249 @@
+use nu_ansi_term::Color;
+use nu_ansi_term::Color::{
+    Black, Blue, Cyan, DarkGray, Fixed, Green, LightBlue, LightCyan, LightGray, LightGreen,
+    LightPurple, LightRed, LightYellow, Purple, Red, Rgb, White, Yellow,
+};
+use nu_ansi_term::Style;
+use serde::Deserialize;
+
+// The [theme] section of the config file
+#[derive(Deserialize, Default)]
+#[serde(rename_all = "kebab-case")]
+pub struct ThemeConfig {
+    // "default" or "colorblind"
+    pub preset: Option<String>,
+    // Draw the tree and the bars with ASCII only
+    pub ascii: Option<bool>,
+    // From the own share of an entry to the shares of its parents, eg: "#=-. "
+    pub bar_chars: Option<String>,
+    // Bars colored by their percent, the first color from 0%, the last up to 100%
+    pub bar_gradient: Option<Vec<String>>,
+    pub biggest_style: Option<String>,
+    pub total_style: Option<String>,
+    pub dim_style: Option<String>,
+}
+
+static BLOCKS: [char; 5] = ['█', '▓', '▒', '░', ' '];
+static ASCII_BLOCKS: [char; 5] = ['#', '=', '-', '.', ' '];
+
+pub struct Theme {
+    pub bar_chars: [char; 5],
+    pub ascii: bool,
+    pub bar_gradient: Vec<Color>,
+    pub biggest: Style,
+    pub total: Option<Style>,
+    pub dim: Style,
+    // The heatmap, from the most recently changed to the oldest
+    pub heatmap: [Color; 5],
+}
+
+impl Default for Theme {
+    fn default() -> Self {
+        Theme {
+            bar_chars: BLOCKS,
+            ascii: false,
+            bar_gradient: vec![],
+            biggest: Red.normal(),
+            total: None,
+            dim: DarkGray.normal(),
+            heatmap: [Green, LightGreen, Yellow, LightRed, Red],
+        }
+    }
+}
+
+impl Theme {
+    // No red against green, blue to orange reads with every kind of color blindness
+    fn colorblind() -> Self {
+        Theme {
+            biggest: Fixed(208).bold(),
+            heatmap: [Fixed(33), Fixed(75), Fixed(250), Fixed(214), Fixed(202)],
+            ..Default::default()
+        }
+    }
+
+    pub fn from_config(config: &ThemeConfig) -> Self {
+        let mut theme = match config.preset.as_deref() {
+            None | Some("default") => Theme::default(),
+            Some("colorblind") => Theme::colorblind(),
+            Some(name) => {
+                eprintln!("Ignoring unknown theme preset: {name}");
+                Theme::default()
+            }
+        };
+        if Some(true) == config.ascii {
+            theme.ascii = true;
+            theme.bar_chars = ASCII_BLOCKS;
+        }
+        if let Some(ref chars) = config.bar_chars {
+            match parse_bar_chars(chars) {
+                Some(bar_chars) => theme.bar_chars = bar_chars,
+                None => eprintln!("Ignoring bar-chars, it needs 4 or 5 different chars: {chars}"),
+            }
+        }
+        if let Some(ref colors) = config.bar_gradient {
+            theme.bar_gradient = colors
+                .iter()
+                .filter_map(|name| {
+                    let color = parse_color(name);
+                    if color.is_none() {
+                        eprintln!("Ignoring unknown color: {name}");
+                    }
+                    color
+                })
+                .collect();
+        }
+        if let Some(style) = get_style(&config.biggest_style) {
+            theme.biggest = style;
+        }
+        if let Some(style) = get_style(&config.total_style) {
+            theme.total = Some(style);
+        }
+        if let Some(style) = get_style(&config.dim_style) {
+            theme.dim = style;
+        }
+        theme
+    }
+
+    pub fn get_gradient_color(&self, percent: f32) -> Option<Color> {
+        let len = self.bar_gradient.len();
+        let index = ((percent * len as f32) as usize).min(len.checked_sub(1)?);
+        Some(self.bar_gradient[index])
+    }
+
+    pub fn get_vertical_line(&self) -> char {
+        if self.ascii { '|' } else { '│' }
+    }
+
+    // The tree is worked out with box drawing chars and only swapped when shown
+    pub fn get_tree_str(&self, indent: &str) -> String {
+        if !self.ascii {
+            return indent.to_string();
+        }
+        indent
+            .chars()
+            .map(|c| match c {
+                '┌' => ',',
+                '└' => '`',
+                '├' | '│' => '|',
+                '─' => '-',
+                '┬' | '┴' => '+',
+                c => c,
+            })
+            .collect()
+    }
+}
+
+fn get_style(value: &Option<String>) -> Option<Style> {
+    let value = value.as_ref()?;
+    let style = parse_style(value);
+    if style.is_none() {
+        eprintln!("Ignoring unknown style: {value}");
+    }
+    style
+}
+
+// A space is added for 4 chars, the last one is for the space past the bar
+fn parse_bar_chars(value: &str) -> Option<[char; 5]> {
+    let mut chars: Vec<char> = value.chars().collect();
+    if chars.len() == 4 {
+        chars.push(' ');
+    }
+    let chars: [char; 5] = chars.try_into().ok()?;
+    // generate_bar tells the parts of the bar apart by their chars
+    let all_different = chars
+        .iter()
+        .enumerate()
+        .all(|(i, c)| !chars[i + 1..].contains(c));
+    all_different.then_some(chars)
+}
+
+// Colors and attributes separated by spaces, eg: "bold yellow", "#ff8800" or "208"
+pub fn parse_style(value: &str) -> Option<Style> {
+    value
+        .split_whitespace()
+        .try_fold(Style::new(), |style, word| {
+            Some(match word.to_lowercase().as_str() {
+                "bold" => style.bold(),
+                "dimmed" => style.dimmed(),
+                "italic" => style.italic(),
+                "underline" => style.underline(),
+                "reverse" => style.reverse(),
+                _ => style.fg(parse_color(word)?),
+            })
+        })
+}
+
+pub fn parse_color(value: &str) -> Option<Color> {
+    if let Some(hex) = value.strip_prefix('#') {
+        if hex.len() != 6 {
+            return None;
+        }
+        let rgb = u32::from_str_radix(hex, 16).ok()?;
+        return Some(Rgb((rgb >> 16) as u8, (rgb >> 8) as u8, rgb as u8));
+    }
+    if let Ok(number) = value.parse() {
+        return Some(Fixed(number));
+    }
+    Some(
+        match value.to_lowercase().replace(['-', '_'], "").as_str() {
+            "black" => Black,
+            "red" => Red,
+            "green" => Green,
+            "yellow" => Yellow,
+            "blue" => Blue,
+            "purple" | "magenta" => Purple,
+            "cyan" => Cyan,
+            "white" => White,
+            "darkgray" | "darkgrey" => DarkGray,
+            "lightred" => LightRed,
+            "lightgreen" => LightGreen,
+            "lightyellow" => LightYellow,
+            "lightblue" => LightBlue,
+            "lightpurple" | "lightmagenta" => LightPurple,
+            "lightcyan" => LightCyan,
+            "lightgray" | "lightgrey" => LightGray,
+            _ => return None,
+        },
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_style() {
+        assert_eq!(parse_style("bold yellow"), Some(Yellow.bold()));
+        assert_eq!(parse_style("light-blue"), Some(LightBlue.normal()));
+        assert_eq!(parse_style("#ff8800"), Some(Rgb(255, 136, 0).normal()));
+        assert_eq!(parse_style("208 underline"), Some(Fixed(208).underline()));
+        assert_eq!(parse_style("blinking red"), None);
+        assert_eq!(parse_style("#ff88"), None);
+    }
+
+    #[test]
+    fn test_parse_bar_chars() {
+        assert_eq!(parse_bar_chars("#=-."), Some(['#', '=', '-', '.', ' ']));
+        assert_eq!(parse_bar_chars("@%+: "), Some(['@', '%', '+', ':', ' ']));
+        assert_eq!(parse_bar_chars("##-."), None);
+        assert_eq!(parse_bar_chars("#="), None);
+    }
+
+    #[test]
+    fn test_theme_from_config() {
+        let theme = Theme::from_config(&ThemeConfig {
+            preset: Some("colorblind".into()),
+            ascii: Some(true),
+            bar_gradient: Some(vec!["green".into(), "yellow".into(), "red".into()]),
+            ..Default::default()
+        });
+        assert_eq!(theme.bar_chars, ASCII_BLOCKS);
+        assert!(!theme.heatmap.contains(&Green));
+        assert_eq!(theme.get_tree_str("│ ├─┬"), "| |-+");
+        assert_eq!(theme.get_gradient_color(0.1), Some(Green));
+        assert_eq!(theme.get_gradient_color(0.5), Some(Yellow));
+        assert_eq!(theme.get_gradient_color(1.0), Some(Red));
+        assert_eq!(Theme::default().get_gradient_color(0.5), None);
+        assert_eq!(Theme::default().get_tree_str("└──"), "└──");
+    }
+}
@@ -0,0 +1,180 @@
+use chrono::{Local, NaiveDate, NaiveDateTime, TimeZone};
+use std::path::Path;
+
+use crate::config::DAY_SECONDS;
+use crate::dir_walker::Operator;
+use crate::platform::get_metadata;
+
+// The parsing shared by -M/-A/-y, --newer/--older and the times of --where,
+// all of them end up as a FilterExpr::Time
+
+// Seconds from: 30s, 15m (minutes), 12h, 90d, 2w, 1y. Without a unit days are used
+pub fn parse_duration(value: &str) -> Option<i64> {
+    let (number, unit) = split_unit(value)?;
+    number.checked_mul(get_unit_seconds(unit)?)
+}
+
+// A point in time: a date, a date and time or else the modification time of a file
+pub fn parse_time_point(value: &str) -> Option<i64> {
+    parse_date(value).or_else(|| {
+        let (_, _, (modified_time, _, _, _)) = get_metadata(Path::new(value), true)?;
+        Some(modified_time)
+    })
+}
+
+// 2026-01-01, 2026-09-01 12:00 or 2026-09-01T12:00:30 in local time
+pub fn parse_date(value: &str) -> Option<i64> {
+    let value = value.trim();
+    let date_time = [
+        "%Y-%m-%d %H:%M:%S",
+        "%Y-%m-%d %H:%M",
+        "%Y-%m-%dT%H:%M:%S",
+        "%Y-%m-%dT%H:%M",
+    ]
+    .iter()
+    .find_map(|format| NaiveDateTime::parse_from_str(value, format).ok())
+    .or_else(|| {
+        NaiveDate::parse_from_str(value, "%Y-%m-%d")
+            .ok()?
+            .and_hms_opt(0, 0, 0)
+    })?;
+    Local
+        .from_local_datetime(&date_time)
+        .earliest()
+        .map(|d| d.timestamp())
+}
+
+// Values of -M/-A/-y: +n is older than n, -n is newer than n and n is exactly n.
+// Days, weeks and years are counted in whole days from `today` (the last
+// midnight), hours, minutes and seconds from `now` and need a sign
+pub fn parse_time_filter(value: &str, now: i64, today: i64) -> Option<(Operator, i64)> {
+    let (sign, rest) = match value.strip_prefix(['+', '-']) {
+        Some(rest) => (value.chars().next(), rest),
+        None => (None, value),
+    };
+    let (number, unit) = split_unit(rest)?;
+    let seconds = number.checked_mul(get_unit_seconds(unit)?)?;
+
+    if is_day_unit(unit) {
+        let time = today - seconds;
+        match sign {
+            Some('+') => Some((Operator::LessThan, time - DAY_SECONDS)),
+            Some(_) => Some((Operator::GreaterThan, time)),
+            None => Some((Operator::Equal, time - DAY_SECONDS)),
+        }
+    } else {
+        let time = now - seconds;
+        match sign {
+            Some('+') => Some((Operator::LessThan, time)),
+            Some(_) => Some((Operator::GreaterThan, time)),
+            None => None,
+        }
+    }
+}
+
+fn split_unit(value: &str) -> Option<(i64, &str)> {
+    let split = value
+        .find(|c: char| !c.is_ascii_digit())
+        .unwrap_or(value.len());
+    let (number, unit) = value.split_at(split);
+    Some((number.parse().ok()?, unit))
+}
+
+fn is_day_unit(unit: &str) -> bool {
+    matches!(unit, "" | "d" | "w" | "y")
+}
+
+fn get_unit_seconds(unit: &str) -> Option<i64> {
+    match unit {
+        "s" => Some(1),
+        "m" => Some(60),
+        "h" => Some(60 * 60),
+        "" | "d" => Some(DAY_SECONDS),
+        "w" => Some(7 * DAY_SECONDS),
+        "y" => Some(365 * DAY_SECONDS),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_duration() {
+        assert_eq!(parse_duration("12h"), Some(12 * 60 * 60));
+        assert_eq!(parse_duration("3"), Some(3 * DAY_SECONDS));
+        assert_eq!(parse_duration("2w"), Some(14 * DAY_SECONDS));
+        assert_eq!(parse_duration("3x"), None);
+        assert_eq!(parse_duration("h"), None);
+    }
+
+    #[test]
+    fn test_parse_date() {
+        let midnight = parse_date("2026-01-01").unwrap();
+        assert_eq!(
+            parse_date("2026-01-01 12:00"),
+            Some(midnight + 12 * 60 * 60)
+        );
+        assert_eq!(
+            parse_date("2026-01-01T12:00:30"),
+            Some(midnight + 12 * 60 * 60 + 30)
+        );
+        assert_eq!(parse_date("2026-13-01"), None);
+        assert_eq!(parse_date("yesterday"), None);
+    }
+
+    #[test]
+    fn test_parse_time_point_from_file() {
+        let file = tempfile::NamedTempFile::new().unwrap();
+        let modified = file.as_file().metadata().unwrap().modified().unwrap();
+        let modified = modified
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap()
+            .as_secs() as i64;
+        assert_eq!(
+            parse_time_point(file.path().to_str().unwrap()),
+            Some(modified)
+        );
+        assert_eq!(parse_time_point("/no/such/file"), None);
+    }
+
+    #[test]
+    fn test_parse_time_filter() {
+        let today = 100 * DAY_SECONDS;
+        let now = today + 10 * 60 * 60;
+        assert!(matches!(
+            parse_time_filter("+2", now, today),
+            Some((Operator::LessThan, t)) if t == today - 3 * DAY_SECONDS
+        ));
+        assert!(matches!(
+            parse_time_filter("-1w", now, today),
+            Some((Operator::GreaterThan, t)) if t == today - 7 * DAY_SECONDS
+        ));
+        assert!(matches!(
+            parse_time_filter("0", now, today),
+            Some((Operator::Equal, t)) if t == today - DAY_SECONDS
+        ));
+        assert!(matches!(
+            parse_time_filter("-6h", now, today),
+            Some((Operator::GreaterThan, t)) if t == now - 6 * 60 * 60
+        ));
+        assert!(matches!(
+            parse_time_filter("+30m", now, today),
+            Some((Operator::LessThan, t)) if t == now - 30 * 60
+        ));
+        // Hours count from now even for whole days
+        assert!(matches!(
+            parse_time_filter("-24h", now, today),
+            Some((Operator::GreaterThan, t)) if t == now - DAY_SECONDS
+        ));
+        assert!(matches!(
+            parse_time_filter("+48h", now, today),
+            Some((Operator::LessThan, t)) if t == now - 2 * DAY_SECONDS
+        ));
+        assert!(parse_time_filter("6h", now, today).is_none());
+        assert!(parse_time_filter("24h", now, today).is_none());
+        assert!(parse_time_filter("-", now, today).is_none());
+        assert!(parse_time_filter("abc", now, today).is_none());
+    }
+}
@@ -1,12 +1,11 @@
 use platform::get_metadata;
+use std::cmp::Ordering;
 use std::collections::HashSet;
+use std::iter::Peekable;
 use std::path::{Path, PathBuf};
+use std::str::Chars;
 
-use crate::config::DAY_SECONDS;
-
-use crate::dir_walker::Operator;
 use crate::platform;
-use regex::Regex;
 
 pub fn simplify_dir_names<P: AsRef<Path>>(dirs: &[P]) -> HashSet<PathBuf> {
     let mut top_level_names: HashSet<PathBuf> = HashSet::with_capacity(dirs.len());
@@ -49,7 +48,7 @@ pub fn get_filesystem_devices<P: AsRef<Path>>(paths: &[P], follow_links: bool) -
             } else {
                 false
             };
-            match get_metadata(p, false, follow_links) {
+            match get_metadata(p, follow_links) {
                 Some((_size, Some((_id, dev)), _time)) => Some(dev),
                 _ => None,
             }
@@ -78,34 +77,24 @@ pub fn canonicalize_absolute_path(path: PathBuf) -> PathBuf {
     }
 }
 
-pub fn is_filtered_out_due_to_regex(filter_regex: &[Regex], dir: &Path) -> bool {
-    if filter_regex.is_empty() {
-        false
-    } else {
-        filter_regex
-            .iter()
-            .all(|f| !f.is_match(&dir.as_os_str().to_string_lossy()))
-    }
-}
-
-pub fn is_filtered_out_due_to_file_time(
-    filter_time: &Option<(Operator, i64)>,
-    actual_time: i64,
-) -> bool {
-    match filter_time {
-        None => false,
-        Some((Operator::Equal, bound_time)) => {
-            !(actual_time >= *bound_time && actual_time < *bound_time + DAY_SECONDS)
+// The ignored paths as the walk of `dirs` will see them, so entries are compared
+// without canonicalizing each one. An absolute path is rebased on the top level
+// directories it is inside of, a top level directory inside it is ignored whole
+pub fn get_ignored_paths(ignored: &[PathBuf], dirs: &HashSet<PathBuf>) -> HashSet<PathBuf> {
+    let mut ignored_paths = HashSet::new();
+    for dir in dirs {
+        let canonical_dir = std::fs::canonicalize(dir).unwrap_or_else(|_| dir.clone());
+        for path in ignored {
+            if !path.is_absolute() {
+                ignored_paths.insert(dir.join(path));
+            } else if let Ok(rest) = path.strip_prefix(&canonical_dir) {
+                ignored_paths.insert(normalize_path(dir.join(rest)));
+            } else if canonical_dir.starts_with(path) {
+                ignored_paths.insert(dir.clone());
+            }
         }
-        Some((Operator::GreaterThan, bound_time)) => actual_time < *bound_time,
-        Some((Operator::LessThan, bound_time)) => actual_time > *bound_time,
     }
-}
-
-pub fn is_filtered_out_due_to_invert_regex(filter_regex: &[Regex], dir: &Path) -> bool {
-    filter_regex
-        .iter()
-        .any(|f| f.is_match(&dir.as_os_str().to_string_lossy()))
+    ignored_paths
 }
 
 fn is_a_parent_of<P: AsRef<Path>>(parent: P, child: P) -> bool {
@@ -114,6 +103,45 @@ fn is_a_parent_of<P: AsRef<Path>>(parent: P, child: P) -> bool {
     child.starts_with(parent) && !parent.starts_with(child)
 }
 
+// Orders names as a person would: file2 before file10, and ignoring case unless
+// that is the only difference
+pub fn natural_cmp(lhs: &str, rhs: &str) -> Ordering {
+    let (mut l, mut r) = (lhs.chars().peekable(), rhs.chars().peekable());
+    loop {
+        let ordering = match (l.peek(), r.peek()) {
+            (None, None) => return lhs.cmp(rhs),
+            (None, Some(_)) => return Ordering::Less,
+            (Some(_), None) => return Ordering::Greater,
+            (Some(a), Some(b)) if a.is_ascii_digit() && b.is_ascii_digit() => {
+                let (l_number, r_number) = (take_digits(&mut l), take_digits(&mut r));
+                let l_number = l_number.trim_start_matches('0');
+                let r_number = r_number.trim_start_matches('0');
+                l_number
+                    .len()
+                    .cmp(&r_number.len())
+                    .then_with(|| l_number.cmp(r_number))
+            }
+            (Some(a), Some(b)) => {
+                let ordering = a.to_lowercase().cmp(b.to_lowercase());
+                l.next();
+                r.next();
+                ordering
+            }
+        };
+        if ordering != Ordering::Equal {
+            return ordering;
+        }
+    }
+}
+
+fn take_digits(chars: &mut Peekable<Chars>) -> String {
+    let mut digits = String::new();
+    while let Some(c) = chars.next_if(char::is_ascii_digit) {
+        digits.push(c);
+    }
+    digits
+}
+
 mod tests {
     #[allow(unused_imports)]
     use super::*;
@@ -176,6 +204,24 @@ mod tests {
         assert_eq!(simplify_dir_names(&["src/", "src_v2"]), correct);
     }
 
+    #[test]
+    fn test_get_ignored_paths() {
+        let dir = std::fs::canonicalize("src").unwrap();
+        let dirs = HashSet::from([PathBuf::from("src")]);
+        let ignored = [PathBuf::from("utils.rs"), dir.join("display.rs")];
+        let correct = HashSet::from([
+            PathBuf::from("src/utils.rs"),
+            PathBuf::from("src/display.rs"),
+        ]);
+        assert_eq!(get_ignored_paths(&ignored, &dirs), correct);
+
+        let ignored = [
+            dir.parent().unwrap().to_path_buf(),
+            PathBuf::from("/elsewhere"),
+        ];
+        assert_eq!(get_ignored_paths(&ignored, &dirs), dirs);
+    }
+
     #[test]
     fn test_is_a_parent_of() {
         assert!(is_a_parent_of("/usr", "/usr/andy"));
@@ -195,4 +241,16 @@ mod tests {
         assert!(is_a_parent_of("/", "/usr"));
         assert!(!is_a_parent_of("/", "/"));
     }
+
+    #[test]
+    fn test_natural_cmp() {
+        assert_eq!(natural_cmp("file2", "file10"), Ordering::Less);
+        assert_eq!(natural_cmp("file10", "file9"), Ordering::Greater);
+        assert_eq!(natural_cmp("File10", "file9"), Ordering::Greater);
+        assert_eq!(natural_cmp("b", "A"), Ordering::Greater);
+        assert_eq!(natural_cmp("a", "a1"), Ordering::Less);
+        assert_eq!(natural_cmp("v1.10.0", "v1.9.2"), Ordering::Greater);
+        assert_eq!(natural_cmp("007", "7"), Ordering::Less);
+        assert_eq!(natural_cmp("same", "same"), Ordering::Equal);
+    }
 }
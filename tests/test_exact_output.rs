@@ -1,3 +1,5 @@
+#![allow(clippy::empty_line_after_doc_comments)]
+
 use assert_cmd::{Command, cargo_bin_cmd};
 use std::ffi::OsStr;
 use std::process::Output;
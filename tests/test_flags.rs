@@ -1,3 +1,5 @@
+#![allow(clippy::empty_line_after_doc_comments)]
+
 use assert_cmd::cargo_bin_cmd;
 use chrono::{Local, TimeZone};
 use std::ffi::OsStr;
@@ -45,6 +47,41 @@ fn test_filetime_output_uses_unix_timestamp() {
     );
 }
 
+#[test]
+fn test_birth_time_output() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    std::fs::write(temp_dir.path().join("new.txt"), b"new").unwrap();
+
+    let mut cmd = cargo_bin_cmd!("dust");
+    let output = cmd
+        .arg("-c")
+        .arg("-m")
+        .arg("b")
+        .arg("--btime")
+        .arg("-1")
+        .arg(temp_dir.path())
+        .unwrap();
+    let stdout = str::from_utf8(&output.stdout).unwrap();
+    let stderr = str::from_utf8(&output.stderr).unwrap();
+
+    // What is shown depends on whether the filesystem records birth times
+    let created = std::fs::metadata(temp_dir.path().join("new.txt"))
+        .unwrap()
+        .created();
+    match created {
+        Ok(created) => {
+            let created: chrono::DateTime<Local> = created.into();
+            assert!(stdout.contains("new.txt"));
+            assert!(stdout.contains(&created.format("%Y-%m-%d").to_string()));
+            assert!(!stdout.contains("unknown"));
+        }
+        Err(_) => {
+            assert!(stdout.contains("unknown"));
+            assert!(stderr.contains("not recorded"));
+        }
+    }
+}
+
 #[test]
 fn test_mtime_filter_uses_unix_timestamp() {
     let temp_dir = tempfile::tempdir().unwrap();
@@ -449,3 +486,655 @@ pub fn test_handle_duplicate_names() {
     assert!(output.contains("dup_name"));
     assert!(!output.contains("test_dir_matching"));
 }
+
+#[cfg(target_family = "unix")]
+#[test]
+pub fn test_compare_sizes_shows_sparse_file() {
+    // A sparse file has a large apparent size but takes almost no disk space
+    let dir = tempfile::Builder::new().tempdir().unwrap();
+    let file = std::fs::File::create(dir.path().join("sparse.img")).unwrap();
+    file.set_len(64 * 1024 * 1024).unwrap();
+
+    let output = build_command(vec!["-c", "--compare-sizes", dir.path().to_str().unwrap()]);
+    assert!(output.contains("64Mi"), "{output}");
+    assert!(output.contains("sparse.img"), "{output}");
+
+    let output = build_command(vec![
+        "-c",
+        "-j",
+        "--size-difference",
+        dir.path().to_str().unwrap(),
+    ]);
+    assert!(output.contains("\"apparent_size\":\"64Mi\""), "{output}");
+}
+
+#[cfg(target_family = "unix")]
+#[test]
+pub fn test_filesystem_info() {
+    let output = build_command(vec!["-c", "--filesystem-info", "tests/test_dir"]);
+    assert!(output.contains("tests/test_dir on "), "{output}");
+    assert!(output.contains("inodes used"), "{output}");
+
+    let output = build_command(vec!["-j", "--filesystem-info", "tests/test_dir"]);
+    assert!(output.contains("\"filesystems\":[{"), "{output}");
+    assert!(output.contains("\"inodes_used\":"), "{output}");
+}
+
+#[cfg(target_family = "unix")]
+#[test]
+pub fn test_filesystem_percent_json() {
+    let output = build_command(vec!["-j", "--filesystem-percent", "tests/test_dir"]);
+    assert!(output.contains("\"percent_of_filesystem\":"), "{output}");
+    assert!(
+        output.contains("\"name\":\"tests/test_dir/many\""),
+        "{output}"
+    );
+    assert_eq!(
+        output.matches("\"percent_of_filesystem\":").count(),
+        4,
+        "{output}"
+    );
+
+    let output = build_command(vec!["-j", "tests/test_dir"]);
+    assert!(!output.contains("percent_of_filesystem"), "{output}");
+}
+
+#[cfg(target_os = "linux")]
+#[test]
+pub fn test_deleted_open_files() {
+    use std::io::Write;
+
+    // This test process holds the deleted file open while dust runs
+    let dir = tempfile::Builder::new().tempdir().unwrap();
+    let path = dir.path().join("held_open.bin");
+    let mut file = std::fs::File::create(&path).unwrap();
+    file.write_all(&[1; 256 * 1024]).unwrap();
+    file.sync_all().unwrap();
+    std::fs::remove_file(&path).unwrap();
+
+    let output = build_command(vec![
+        "-c",
+        "--deleted-open-files",
+        dir.path().to_str().unwrap(),
+    ]);
+    assert!(output.contains("(deleted, still open)"), "{output}");
+    assert!(output.contains("held_open.bin [pid "), "{output}");
+    drop(file);
+}
+
+#[cfg(target_family = "unix")]
+#[test]
+pub fn test_reconcile() {
+    let output = build_command(vec!["-c", "--reconcile", "-X", "many", "tests/test_dir"]);
+    assert!(
+        output.contains("Difference from filesystem used space:"),
+        "{output}"
+    );
+    assert!(output.contains("  excluded paths: "), "{output}");
+    assert!(
+        !output.contains("  excluded paths:           0B"),
+        "{output}"
+    );
+    assert!(output.contains("  unexplained: "), "{output}");
+    assert!(
+        output.contains("tests/test_dir is not a mount point"),
+        "{output}"
+    );
+}
+
+#[test]
+pub fn test_gitignore() {
+    let dir = tempfile::Builder::new().tempdir().unwrap();
+    std::fs::create_dir_all(dir.path().join("build")).unwrap();
+    std::fs::create_dir_all(dir.path().join("src")).unwrap();
+    std::fs::write(dir.path().join(".gitignore"), "build/\n*.o\n").unwrap();
+    std::fs::write(dir.path().join("build/out.bin"), [1; 4096]).unwrap();
+    std::fs::write(dir.path().join("src/main.o"), [1; 4096]).unwrap();
+    std::fs::write(dir.path().join("src/main.c"), [1; 4096]).unwrap();
+    let path = dir.path().to_str().unwrap();
+
+    let output = build_command(vec!["-c", "--gitignore", path]);
+    assert!(output.contains("main.c"), "{output}");
+    assert!(!output.contains("main.o"), "{output}");
+    assert!(!output.contains("build"), "{output}");
+
+    let output = build_command(vec!["-c", "--only-gitignored", path]);
+    assert!(output.contains("out.bin"), "{output}");
+    assert!(output.contains("main.o"), "{output}");
+    assert!(!output.contains("main.c"), "{output}");
+    assert!(!output.contains(".gitignore"), "{output}");
+}
+
+#[test]
+pub fn test_exclude_glob() {
+    let output = build_command(vec!["-c", "--exclude", "a_file", "tests/test_dir/"]);
+    assert!(!output.contains("a_file"), "{output}");
+    assert!(output.contains("hello_file"), "{output}");
+
+    let dir = tempfile::Builder::new().tempdir().unwrap();
+    let exclude_file = dir.path().join("excludes");
+    std::fs::write(&exclude_file, "# comment\n**/many/hello_*\n").unwrap();
+    let output = build_command(vec![
+        "-c",
+        "--exclude-from",
+        exclude_file.to_str().unwrap(),
+        "tests/test_dir/",
+    ]);
+    assert!(output.contains("a_file"), "{output}");
+    assert!(!output.contains("hello_file"), "{output}");
+}
+
+#[test]
+pub fn test_dustignore() {
+    let dir = tempfile::Builder::new().tempdir().unwrap();
+    std::fs::create_dir_all(dir.path().join("project/cache")).unwrap();
+    std::fs::write(dir.path().join("project/.dustignore"), "cache/\n").unwrap();
+    std::fs::write(dir.path().join("project/cache/blob.bin"), [1; 4096]).unwrap();
+    std::fs::write(dir.path().join("cache"), [1; 4096]).unwrap();
+    let path = dir.path().to_str().unwrap();
+
+    // The pattern only applies below the directory holding the .dustignore
+    let output = build_command(vec!["-c", "-p", path]);
+    assert!(!output.contains("blob.bin"), "{output}");
+    assert!(output.contains(&format!("{path}/cache")), "{output}");
+
+    let output = build_command(vec!["-c", "--no-dustignore", path]);
+    assert!(output.contains("blob.bin"), "{output}");
+}
+
+#[test]
+pub fn test_where_expression() {
+    let output = build_command(vec![
+        "-c",
+        "--where",
+        "name = a_file or (path ~ hello and not size > 1M)",
+        "tests/test_dir/",
+    ]);
+    assert!(output.contains("a_file"), "{output}");
+    assert!(output.contains("hello_file"), "{output}");
+
+    let output = build_command(vec!["-c", "--where", "name != a_file", "tests/test_dir/"]);
+    assert!(!output.contains("a_file"), "{output}");
+    assert!(output.contains("hello_file"), "{output}");
+}
+
+#[test]
+pub fn test_where_expression_parse_error() {
+    let mut cmd = cargo_bin_cmd!("dust");
+    cmd.args(["--where", "size >> 1M", "tests/test_dir/"]);
+    let output_error = cmd.unwrap_err();
+    let result = output_error.as_output().unwrap();
+    let stderr = str::from_utf8(&result.stderr).unwrap();
+    assert!(stderr.contains("Invalid --where expression"), "{stderr}");
+    assert!(stderr.contains("size >> 1M\n"), "{stderr}");
+}
+
+#[test]
+pub fn test_max_size_and_size_range() {
+    let dir = tempfile::Builder::new().tempdir().unwrap();
+    std::fs::write(dir.path().join("small"), [1; 100]).unwrap();
+    std::fs::write(dir.path().join("big"), [1; 100_000]).unwrap();
+    let path = dir.path().to_str().unwrap();
+
+    let output = build_command(vec!["-c", "-s", "--max-size", "1K", path]);
+    assert!(output.contains("small"), "{output}");
+    assert!(!output.contains("big"), "{output}");
+
+    let output = build_command(vec!["-c", "-s", "--size-range", "1K..", path]);
+    assert!(!output.contains("small"), "{output}");
+    assert!(output.contains("big"), "{output}");
+}
+
+#[test]
+pub fn test_newer_older_and_sub_day_time_filters() {
+    let dir = tempfile::Builder::new().tempdir().unwrap();
+    let old_file = dir.path().join("old.txt");
+    std::fs::write(&old_file, b"old").unwrap();
+    std::fs::write(dir.path().join("new.txt"), b"new").unwrap();
+    let old_time = Local
+        .from_local_datetime(
+            &chrono::NaiveDate::from_ymd_opt(2020, 6, 1)
+                .unwrap()
+                .and_hms_opt(12, 0, 0)
+                .unwrap(),
+        )
+        .single()
+        .unwrap();
+    let file = OpenOptions::new().write(true).open(&old_file).unwrap();
+    file.set_times(FileTimes::new().set_modified(old_time.into()))
+        .unwrap();
+    let path = dir.path().to_str().unwrap();
+
+    let output = build_command(vec!["-c", "--older", "2021-01-01", path]);
+    assert!(output.contains("old.txt"), "{output}");
+    assert!(!output.contains("new.txt"), "{output}");
+
+    let output = build_command(vec!["-c", "--newer", "2020-06-01 13:00", path]);
+    assert!(!output.contains("old.txt"), "{output}");
+    assert!(output.contains("new.txt"), "{output}");
+
+    // Against the modification time of a reference file
+    let output = build_command(vec!["-c", "--newer", old_file.to_str().unwrap(), path]);
+    assert!(output.contains("new.txt"), "{output}");
+
+    let output = build_command(vec!["-c", "-M", "-6h", path]);
+    assert!(!output.contains("old.txt"), "{output}");
+    assert!(output.contains("new.txt"), "{output}");
+}
+
+#[cfg(unix)]
+#[test]
+pub fn test_owner_and_permission_filters() {
+    use std::os::unix::fs::MetadataExt;
+    use std::os::unix::fs::PermissionsExt;
+
+    let dir = tempfile::Builder::new().tempdir().unwrap();
+    let shared = dir.path().join("shared.txt");
+    let private = dir.path().join("private.txt");
+    std::fs::write(&shared, b"shared").unwrap();
+    std::fs::write(&private, b"private").unwrap();
+    std::fs::set_permissions(&shared, std::fs::Permissions::from_mode(0o666)).unwrap();
+    std::fs::set_permissions(&private, std::fs::Permissions::from_mode(0o600)).unwrap();
+    let uid = std::fs::metadata(&shared).unwrap().uid().to_string();
+    let path = dir.path().to_str().unwrap();
+
+    let output = build_command(vec!["-c", "--perm", "/o+w", path]);
+    assert!(output.contains("shared.txt"), "{output}");
+    assert!(!output.contains("private.txt"), "{output}");
+
+    let output = build_command(vec!["-c", "--perm", "600", "--user", &uid, path]);
+    assert!(!output.contains("shared.txt"), "{output}");
+    assert!(output.contains("private.txt"), "{output}");
+
+    let other_users = format!("user != {uid}");
+    let output = build_command(vec!["-c", "--where", &other_users, path]);
+    assert!(!output.contains("shared.txt"), "{output}");
+    assert!(!output.contains("private.txt"), "{output}");
+}
+
+#[test]
+pub fn test_regex_match_on_and_ignore_case() {
+    let dir = tempfile::Builder::new().tempdir().unwrap();
+    std::fs::create_dir_all(dir.path().join("build/out")).unwrap();
+    std::fs::write(dir.path().join("build/out/a.o"), b"object").unwrap();
+    std::fs::write(dir.path().join("photo.JPG"), b"photo").unwrap();
+    let path = dir.path().to_str().unwrap();
+
+    let output = build_command(vec!["-c", "-e", "^build/", path]);
+    assert!(!output.contains("a.o"), "{output}");
+    let output = build_command(vec!["-c", "-e", "^build/", "--match-on", "relative", path]);
+    assert!(output.contains("a.o"), "{output}");
+    assert!(!output.contains("photo.JPG"), "{output}");
+
+    let output = build_command(vec!["-c", "-e", "\\.jpg$", path]);
+    assert!(!output.contains("photo.JPG"), "{output}");
+    let output = build_command(vec!["-c", "-e", "\\.jpg$", "--ignore-case", path]);
+    assert!(output.contains("photo.JPG"), "{output}");
+
+    let output = build_command(vec!["-c", "-v", "^a", "--match-on", "name", path]);
+    assert!(!output.contains("a.o"), "{output}");
+    assert!(output.contains("photo.JPG"), "{output}");
+}
+
+#[test]
+pub fn test_show_excluded() {
+    let dir = tempfile::Builder::new().tempdir().unwrap();
+    std::fs::create_dir(dir.path().join(".cache")).unwrap();
+    std::fs::write(dir.path().join(".cache/blob"), [1; 5000]).unwrap();
+    std::fs::write(dir.path().join("a.log"), b"log").unwrap();
+    std::fs::write(dir.path().join("b.txt"), b"text").unwrap();
+    let path = dir.path().to_str().unwrap();
+
+    let output = build_command(vec!["-c", "-i", "-v", "\\.log$", "--show-excluded", path]);
+    let summary = output.lines().last().unwrap();
+    assert!(summary.starts_with("Excluded: hidden: "), "{summary}");
+    assert!(summary.contains(" in 1 file; regex -v: "), "{summary}");
+
+    let output = build_command(vec!["-c", "--show-excluded", path]);
+    assert!(output.ends_with("Excluded: nothing\n"), "{output}");
+
+    let output = build_command(vec!["-j", "-i", "--show-excluded", path]);
+    assert!(
+        output.contains(r#""reasons":[{"reason":"hidden","files":1,"bytes":"#),
+        "{output}"
+    );
+}
+
+#[test]
+pub fn test_show_remainder() {
+    let dir = tempfile::Builder::new().tempdir().unwrap();
+    for (name, size) in [("big", 20_000), ("mid", 12_000), ("a", 5000), ("b", 5000)] {
+        std::fs::write(dir.path().join(name), vec![1; size]).unwrap();
+    }
+    let path = dir.path().to_str().unwrap();
+
+    let output = build_command(vec!["-c", "-s", "-n", "2", "--show-remainder", path]);
+    assert!(output.contains("(2 more: 9.8Ki)"), "{output}");
+    assert!(
+        !output.contains("── a ") && !output.contains("── b "),
+        "{output}"
+    );
+
+    // The remainder is the last entry whatever the order
+    let output = build_command(vec![
+        "-j",
+        "-s",
+        "-n",
+        "2",
+        "--show-remainder",
+        "--sort",
+        "name",
+        path,
+    ]);
+    let last_name = output.rsplit(r#""name":"#).next().unwrap();
+    assert!(last_name.starts_with(r#""(2 more: "#), "{output}");
+
+    // Nothing is left out so there is no remainder
+    let output = build_command(vec!["-c", "-s", "--show-remainder", path]);
+    assert!(!output.contains(" more: "), "{output}");
+}
+
+#[test]
+pub fn test_per_dir_and_min_parent_percent() {
+    let dir = tempfile::Builder::new().tempdir().unwrap();
+    let big = dir.path().join("big");
+    let small = dir.path().join("small");
+    std::fs::create_dir_all(&big).unwrap();
+    std::fs::create_dir_all(&small).unwrap();
+    for i in 0..5 {
+        std::fs::write(big.join(format!("b{i}")), vec![1; 100_000]).unwrap();
+    }
+    std::fs::write(small.join("s0"), vec![1; 5000]).unwrap();
+    std::fs::write(small.join("s1"), vec![1; 100]).unwrap();
+    let path = dir.path().to_str().unwrap();
+    let small_path = small.to_str().unwrap();
+
+    // With the biggest entries overall the small directory gets no lines
+    let output = build_command(vec!["-c", "-s", "-n", "5", path]);
+    assert!(!output.contains("s0"), "{output}");
+
+    let output = build_command(vec!["-c", "-s", "-d", "2", "--per-dir", "2", path]);
+    assert_eq!(output.matches("── b").count(), 2, "{output}");
+    assert!(output.contains("s0") && output.contains("s1"), "{output}");
+
+    // Each directory gets its lines, -n does not cap them
+    let output = build_command(vec![
+        "-c",
+        "-s",
+        "-d",
+        "2",
+        "--per-dir",
+        "2",
+        "-n",
+        "3",
+        path,
+    ]);
+    assert_eq!(output.matches("── b").count(), 2, "{output}");
+    assert!(output.contains("s0") && output.contains("s1"), "{output}");
+
+    let output = build_command(vec![
+        "-c",
+        "-s",
+        "-d",
+        "2",
+        "--min-parent-percent",
+        "10",
+        path,
+    ]);
+    // small is 1% of the whole, s1 2% of small
+    assert!(output.contains("b0"), "{output}");
+    assert!(!output.contains("small"), "{output}");
+
+    let output = build_command(vec!["-c", "-s", "--min-parent-percent", "10", small_path]);
+    assert!(output.contains("s0"), "{output}");
+    assert!(!output.contains("s1"), "{output}");
+}
+
+#[test]
+pub fn test_min_size_as_percent() {
+    let dir = tempfile::Builder::new().tempdir().unwrap();
+    let big = dir.path().join("big");
+    let small = dir.path().join("small");
+    std::fs::create_dir_all(&big).unwrap();
+    std::fs::create_dir_all(&small).unwrap();
+    std::fs::write(big.join("b0"), vec![1; 100_000]).unwrap();
+    std::fs::write(small.join("s0"), vec![1; 5000]).unwrap();
+    std::fs::write(small.join("s1"), vec![1; 100]).unwrap();
+    let path = dir.path().to_str().unwrap();
+
+    // small is 5% of the total, s1 2% of small
+    let output = build_command(vec!["-c", "-s", "-z", "10%", path]);
+    assert!(output.contains("b0"), "{output}");
+    assert!(!output.contains("small"), "{output}");
+
+    let output = build_command(vec!["-c", "-s", "-z", "3%p", path]);
+    assert!(output.contains("s0"), "{output}");
+    assert!(!output.contains("s1"), "{output}");
+
+    // Times have no percent
+    let output = cargo_bin_cmd!("dust")
+        .args(["-m", "m", "-z", "10%", path])
+        .output()
+        .unwrap();
+    assert!(!output.status.success());
+    assert!(str::from_utf8(&output.stderr).unwrap().contains("filetime"));
+}
+
+#[test]
+pub fn test_flat_listing() {
+    let dir = tempfile::Builder::new().tempdir().unwrap();
+    let a = dir.path().join("a");
+    let b = dir.path().join("b");
+    std::fs::create_dir_all(a.join("inner")).unwrap();
+    std::fs::create_dir_all(&b).unwrap();
+    std::fs::write(a.join("inner").join("f"), vec![1; 50_000]).unwrap();
+    std::fs::write(b.join("f"), vec![1; 100_000]).unwrap();
+    let path = dir.path().to_str().unwrap();
+    let a_line = format!("{}", a.display());
+    let b_line = format!("{}", b.display());
+    let inner_line = format!("{}", a.join("inner").display());
+
+    // Full paths, the biggest last, no tree drawing
+    let output = build_command(vec!["-c", "--flat", path]);
+    assert!(!output.contains("──"), "{output}");
+    assert!(!output.contains(&inner_line), "{output}");
+    let a_pos = output.find(&a_line).unwrap();
+    assert!(a_pos < output.find(&b_line).unwrap(), "{output}");
+
+    let output = build_command(vec!["-c", "--flat", "--sort", "name", "-r", path]);
+    assert!(
+        output.find(&b_line).unwrap() < output.find(&a_line).unwrap(),
+        "{output}"
+    );
+
+    let output = build_command(vec!["-c", "--flat", "-d", "2", path]);
+    assert!(output.contains(&inner_line), "{output}");
+    assert!(!output.contains(&b_line), "{output}");
+
+    let output = build_command(vec!["-c", "--flat=upto", "-d", "2", path]);
+    assert_eq!(output.lines().count(), 4, "{output}");
+}
+
+#[test]
+pub fn test_focus() {
+    let dir = tempfile::Builder::new().tempdir().unwrap();
+    let inner = dir.path().join("a").join("inner");
+    std::fs::create_dir_all(&inner).unwrap();
+    std::fs::create_dir_all(dir.path().join("b")).unwrap();
+    std::fs::write(inner.join("deep_file"), vec![1; 50_000]).unwrap();
+    std::fs::write(dir.path().join("b").join("other_file"), vec![1; 50_000]).unwrap();
+    let path = dir.path().to_str().unwrap();
+
+    let output = build_command(vec!["-c", "--focus", inner.to_str().unwrap(), path]);
+    let breadcrumb = output.lines().next().unwrap();
+    assert!(breadcrumb.starts_with(path), "{output}");
+    assert!(
+        breadcrumb.contains(" > a ") && breadcrumb.contains(" > inner "),
+        "{output}"
+    );
+    assert!(output.contains("deep_file"), "{output}");
+    assert!(!output.contains("other_file"), "{output}");
+
+    let output = build_command(vec!["-j", "--focus", inner.to_str().unwrap(), path]);
+    assert!(output.contains(r#""ancestors":[{"#), "{output}");
+    assert!(output.contains(r#""percent":100.0"#), "{output}");
+
+    let mut cmd = cargo_bin_cmd!("dust");
+    cmd.arg("-P").arg("--focus").arg("/no/such/focus").arg(path);
+    let output_error = cmd.unwrap_err();
+    let result = output_error.as_output().unwrap();
+    let stderr = str::from_utf8(&result.stderr).unwrap();
+    assert!(stderr.contains("not in the scanned tree"), "{stderr}");
+}
+
+#[test]
+pub fn test_sort_orders() {
+    let dir = tempfile::Builder::new().tempdir().unwrap();
+    let many = dir.path().join("many");
+    std::fs::create_dir_all(&many).unwrap();
+    for i in 0..3 {
+        std::fs::write(many.join(format!("small{i}")), vec![1; 10]).unwrap();
+    }
+    std::fs::write(dir.path().join("file2.txt"), vec![1; 30_000]).unwrap();
+    std::fs::write(dir.path().join("file10.log"), vec![1; 20_000]).unwrap();
+    let path = dir.path().to_str().unwrap();
+    let position = |output: &str, name: &str| output.find(name).unwrap();
+
+    // Drawn from the top: smallest first by default
+    let output = build_command(vec!["-c", "-d", "1", path]);
+    assert!(
+        position(&output, "file10.log") < position(&output, "file2.txt"),
+        "{output}"
+    );
+
+    let output = build_command(vec!["-c", "-d", "1", "--sort", "name", path]);
+    assert!(
+        position(&output, "file2.txt") < position(&output, "file10.log"),
+        "{output}"
+    );
+    assert!(
+        position(&output, "file10.log") < position(&output, "many"),
+        "{output}"
+    );
+
+    let output = build_command(vec!["-c", "-d", "1", "--sort", "name", "-r", path]);
+    assert!(
+        position(&output, "many") < position(&output, "file2.txt"),
+        "{output}"
+    );
+
+    let output = build_command(vec!["-c", "-d", "1", "--sort", "count", path]);
+    assert!(
+        position(&output, "file2.txt") < position(&output, "many"),
+        "{output}"
+    );
+
+    let output = build_command(vec!["-c", "-d", "1", "--sort", "extension", path]);
+    assert!(
+        position(&output, "many") < position(&output, "file10.log"),
+        "{output}"
+    );
+    assert!(
+        position(&output, "file10.log") < position(&output, "file2.txt"),
+        "{output}"
+    );
+}
+
+#[test]
+pub fn test_columns() {
+    let dir = tempfile::Builder::new().tempdir().unwrap();
+    let sub = dir.path().join("sub");
+    std::fs::create_dir_all(sub.join("inner")).unwrap();
+    for i in 0..3 {
+        std::fs::write(sub.join(format!("f{i}")), vec![1; 10]).unwrap();
+    }
+    std::fs::write(sub.join("inner").join("g"), vec![1; 10]).unwrap();
+    let path = dir.path().to_str().unwrap();
+
+    let output = build_command(vec!["-c", "-d", "1", "--columns", "files,dirs", path]);
+    let sub_line = output.lines().find(|l| l.contains("── sub")).unwrap();
+    let columns: Vec<_> = sub_line.split_whitespace().take(2).collect();
+    assert_eq!(columns, ["4", "2"], "{output}");
+
+    let output = build_command(vec!["-c", "-d", "1", "--columns", "apparent,newest", path]);
+    let sub_line = output.lines().find(|l| l.contains("── sub")).unwrap();
+    let newest = sub_line.split_whitespace().nth(1).unwrap();
+    assert!(
+        newest.starts_with(&Local::now().format("%Y-%m-%d").to_string()),
+        "{output}"
+    );
+}
+
+#[test]
+pub fn test_heatmap() {
+    let dir = tempfile::Builder::new().tempdir().unwrap();
+    let old_file = dir.path().join("old.txt");
+    std::fs::write(&old_file, vec![1; 100]).unwrap();
+    std::fs::write(dir.path().join("new.txt"), vec![1; 100]).unwrap();
+    let old_time = Local
+        .with_ymd_and_hms(2015, 1, 1, 12, 0, 0)
+        .single()
+        .unwrap();
+    let file = OpenOptions::new().write(true).open(&old_file).unwrap();
+    file.set_times(FileTimes::new().set_modified(old_time.into()))
+        .unwrap();
+    let path = dir.path().to_str().unwrap();
+
+    let output = build_command(vec!["-C", "--heatmap=name", path]);
+    let old_line = output.lines().find(|l| l.contains("old.txt")).unwrap();
+    let new_line = output.lines().find(|l| l.contains("new.txt")).unwrap();
+    // Red and green
+    assert!(old_line.contains("\u{1b}[31mold.txt"), "{output}");
+    assert!(new_line.contains("\u{1b}[32mnew.txt"), "{output}");
+
+    let output = build_command(vec!["-C", "--heatmap", path]);
+    let old_line = output.lines().find(|l| l.contains("old.txt")).unwrap();
+    assert!(old_line.contains("│\u{1b}[31m"), "{output}");
+
+    let output = build_command(vec!["-c", "--heatmap", path]);
+    assert!(!output.contains('\u{1b}'), "{output}");
+}
+
+#[test]
+pub fn test_theme() {
+    let dir = tempfile::Builder::new().tempdir().unwrap();
+    let config = dir.path().join("config.toml");
+    std::fs::write(
+        &config,
+        "[theme]\nascii=true\nbar-chars=\"@%+:\"\ntotal-style=\"bold blue\"\n",
+    )
+    .unwrap();
+    let config = config.to_str().unwrap();
+
+    let output = build_command(vec!["-c", "--config", config, "tests/test_dir"]);
+    assert!(output.contains(",-+ test_dir"), "{output}");
+    assert!(output.contains("|@@@"), "{output}");
+    assert!(!output.contains('│'), "{output}");
+    assert!(!output.contains('█'), "{output}");
+
+    let output = build_command(vec!["-C", "--config", config, "tests/test_dir"]);
+    let total_line = output.lines().find(|l| l.contains("test_dir")).unwrap();
+    assert!(total_line.contains("\u{1b}[1;34mtest_dir"), "{output}");
+}
+
+#[test]
+pub fn test_percent_of_parent() {
+    let paths = ["tests/test_dir2/dir", "tests/test_dir2/dir_substring"];
+    let output = build_command([&["-c"], &paths[..]].concat());
+    let hello = output.lines().find(|l| l.contains("hello")).unwrap();
+    assert!(hello.ends_with(" 25%"), "{output}");
+
+    let output = build_command([&["-c", "--percent-of-parent"], &paths[..]].concat());
+    let hello = output.lines().find(|l| l.contains("hello")).unwrap();
+    assert!(hello.ends_with(" 50%"), "{output}");
+    // A full width bar for the parent
+    assert!(hello.contains("░ │"), "{output}");
+
+    let output = build_command([&["-j", "--percent-of-parent"], &paths[..]].concat());
+    assert!(
+        output.contains(r#""percent":{"of_total":25.0,"of_parent":50.0}"#),
+        "{output}"
+    );
+    // The added fields keep the order of the plain output
+    assert!(output.starts_with(r#"{"size":"#), "{output}");
+}